@@ -0,0 +1,2742 @@
+//! Application state and everything the keys do to it, independent of the
+//! terminal so it can be driven from tests.
+
+use crate::{
+    clock::Clock,
+    format::{
+        collapse_whitespace, field_value, format_ago, format_day, format_hours_minutes, local_day,
+        unknown_placeholders, LIST_PLACEHOLDERS,
+    },
+    keymap::{build_keymap, controls_hint, key_help, Action},
+    settings::{load_settings, save_settings, Settings, Text},
+    sound::play_alert,
+    storage::{
+        archive_path_for, export_markdown, find_projects, import_plain, is_archive_file,
+        load_distractions, load_todos, log_session, modified_at, save_todos, DATA_FILE_ENV,
+        DISTRACTIONS_FILE, PROJECTS_DIR, SESSION_LOG_FILE, SUMMARY_PREFIX, TODO_FILE,
+    },
+    task::{
+        sessions_per_day, sessions_per_hour, LanguageStats, PomodoroState, Priority, Project,
+        Session, Task, EXTENSION, LABELS, MAX_EXTENSIONS, WORK_DURATION,
+    },
+};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant, SystemTime},
+};
+
+pub enum InputMode {
+    Form,
+    Minutes,
+    Checklist,
+    Project,
+    Distraction,
+    Split,
+    Intent,
+    Import,
+    Command,
+    NoTyping,
+}
+
+/// Field labels of the new-task form, in Tab order.
+pub const FORM_FIELDS: [Text; 4] = [
+    Text::FieldTask,
+    Text::FieldLanguage,
+    Text::Priority,
+    Text::FieldEstimate,
+];
+const FIELD_NAME: usize = 0;
+const FIELD_LANGUAGE: usize = 1;
+pub const FIELD_PRIORITY: usize = 2;
+pub const FIELD_ESTIMATE: usize = 3;
+
+/// The new-task form: one text value per entry of `FORM_FIELDS` plus the
+/// field that currently receives typing.
+#[derive(Default)]
+pub struct TaskForm {
+    pub values: [String; FORM_FIELDS.len()],
+    pub focused: usize,
+}
+
+impl TaskForm {
+    fn focused_value(&mut self) -> &mut String {
+        &mut self.values[self.focused]
+    }
+
+    fn next_field(&mut self) {
+        self.focused = (self.focused + 1) % FORM_FIELDS.len();
+    }
+
+    fn prev_field(&mut self) {
+        self.focused = (self.focused + FORM_FIELDS.len() - 1) % FORM_FIELDS.len();
+    }
+}
+
+/// One row of the task list as drawn: a section header, or a task by its
+/// index into `todos`.
+pub enum ListRow {
+    Header(&'static str),
+    Task(usize),
+}
+
+/// The section a task is listed under when grouping by state.
+fn state_group(task: &Task) -> usize {
+    if !matches!(task.pomodoro_state, PomodoroState::Idle) {
+        0
+    } else if task.done {
+        2
+    } else {
+        1
+    }
+}
+
+const GROUP_HEADERS: [&str; 3] = ["Running", "Idle", "Completed"];
+
+pub enum View {
+    Tasks,
+    Stats,
+    Settings,
+    Distractions,
+    Archive,
+    Help,
+}
+
+/// An action waiting on a y/n answer.
+pub enum Confirmation {
+    Quit,
+    ResetStats,
+    /// Starting a session, with its custom length, on a task marked done.
+    StartDone(Option<Duration>),
+    /// The data file changed on disk while changes here were unsaved.
+    Reload,
+    /// Also zeroing the count of a task just reopened.
+    RestartCycle,
+}
+
+impl Confirmation {
+    pub fn prompt(&self) -> &'static str {
+        match self {
+            Confirmation::Quit => "A session is running — quit anyway? (y/n)",
+            Confirmation::ResetStats => {
+                "Reset pomodoro counts, focus time and history for every task? (y/n)"
+            }
+            Confirmation::StartDone(_) => {
+                "This task is marked done — start a session on it anyway? (y/n)"
+            }
+            Confirmation::Reload => {
+                "The task file changed on disk — reload it and drop unsaved changes? (y/n)"
+            }
+            Confirmation::RestartCycle => {
+                "Reopened. Start its pomodoro count over? It stays in the lifetime total. (y/n)"
+            }
+        }
+    }
+}
+
+struct StatusMessage {
+    text: String,
+    shown_at: Instant,
+    persistent: bool,
+}
+
+const MAX_CUSTOM_MINUTES: u64 = 600;
+const SKIPPED_BREAKS_WARNING: u32 = 3;
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+const FLASH_DURATION: Duration = Duration::from_millis(500);
+/// Opens a folder in the system file manager.
+#[cfg(target_os = "macos")]
+const FILE_MANAGER: &str = "open";
+#[cfg(windows)]
+const FILE_MANAGER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", windows)))]
+const FILE_MANAGER: &str = "xdg-open";
+
+/// Commands that put their stdin on the system clipboard, tried in order.
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(windows)]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Command-line switches, fixed for the whole run.
+#[derive(Clone, Copy, Default)]
+pub struct Mode {
+    pub read_only: bool,
+    pub no_save: bool,
+    pub debug: bool,
+}
+
+pub struct App {
+    pub todos: Vec<Task>,
+    pub data_path: String,
+    archive_path: String,
+    pub form: TaskForm,
+    pub minutes_input: String,
+    pub checklist_input: String,
+    pub selected_index: usize,
+    pub input_mode: InputMode,
+    pub cursor_position: usize,
+    status_message: Option<StatusMessage>,
+    /// Why the task list can't be read or written. Unlike the status line it
+    /// stays up until a save goes through.
+    pub error_banner: Option<String>,
+    /// The data file's modification time as of the last load or save, to
+    /// notice other programs writing to it.
+    data_mtime: Option<SystemTime>,
+    pub view: View,
+    pub focus_lock: bool,
+    pub settings: Settings,
+    pub settings_index: usize,
+    skipped_breaks: u32,
+    pub pending_confirm: Option<Confirmation>,
+    pub last_activity: Instant,
+    pub snapshot_scroll: u16,
+    pub flash_until: Option<Instant>,
+    pub read_only: bool,
+    /// Dry run: the task list, archive and session log are read but never
+    /// written.
+    pub no_save: bool,
+    /// Hooks and other helper commands that haven't exited yet, with their
+    /// command line.
+    hooks: Vec<(String, Child)>,
+    /// When timer changes not yet on disk should be written.
+    save_due: Option<Instant>,
+    /// Start of the standalone timer started with `T`.
+    pub quick_timer: Option<Instant>,
+    /// Tasks paused because the terminal lost focus.
+    blur_paused: Vec<usize>,
+    /// Hides everything but the timer.
+    pub zen: bool,
+    /// The main list first, then everything in the projects directory.
+    pub projects: Vec<Project>,
+    pub active_project: usize,
+    pub project_input: String,
+    /// Name of the task split off the selected one.
+    pub split_input: String,
+    pub intent_input: String,
+    /// Path of a plain list of task names to import.
+    pub import_input: String,
+    pub command_input: String,
+    /// The custom length of the session waiting on its intent prompt.
+    pending_session: Option<Duration>,
+    /// Append-only record of completed sessions, next to the main list.
+    log_path: String,
+    /// Direction the next `A` sort uses.
+    sort_descending: bool,
+    /// The language last given to a task, for `L` to hand on to others.
+    last_language: Option<String>,
+    /// Work sessions completed since the app started.
+    sessions_this_run: u32,
+    /// Set once the auto-exit target is reached; printed after the terminal
+    /// is restored.
+    pub farewell: Option<String>,
+    /// The archive file's tasks, read when the archive or stats view opens.
+    pub archived: Vec<Task>,
+    pub archive_index: usize,
+    /// Thoughts jotted down mid-session to deal with later.
+    pub distractions: Vec<String>,
+    pub distraction_input: String,
+    distractions_path: String,
+    /// The task 'p' works on, independent of the list selection.
+    pub active_task: Option<usize>,
+    /// The task whose timer was started last. While that timer runs, is
+    /// paused or is on a break, 'p' and the gauge follow it rather than
+    /// the selection.
+    focus_task: Option<usize>,
+    pub keymap: HashMap<char, Action>,
+    pub controls_hint: String,
+    /// Every binding, listed by the help view.
+    pub key_help: Vec<String>,
+    /// One line per task instead of two.
+    pub compact_list: bool,
+    /// Lists running, idle and completed tasks under their own headers.
+    pub group_by_state: bool,
+    /// Shows the selected task's recent days as a sparkline in place of the
+    /// snapshot.
+    pub task_sparkline: bool,
+    /// Enables the F9 shortcut that finishes the current phase.
+    pub debug: bool,
+    pub clock: Clock,
+}
+
+impl App {
+    /// `data_path` comes from `--file`; without it `$POMODORO_FILE` and then
+    /// the default file name are used.
+    /// The task list to open: `--file`, then $POMODORO_FILE, then the default.
+    pub fn resolve_data_path(file: Option<String>) -> String {
+        file.or_else(|| env::var(DATA_FILE_ENV).ok().filter(|path| !path.is_empty()))
+            .unwrap_or_else(|| TODO_FILE.to_string())
+    }
+
+    /// Loads the list and settings, reading time from `clock`. Startup chores
+    /// such as resetting recurring tasks already honour `mode` and may save,
+    /// so the caller should hold the data lock first.
+    pub fn new(data_path: String, mode: Mode, clock: Clock) -> Self {
+        let archive_path = archive_path_for(&data_path);
+        let log_path = Path::new(&data_path)
+            .with_file_name(SESSION_LOG_FILE)
+            .to_string_lossy()
+            .into_owned();
+        let distractions_path = Path::new(&data_path)
+            .with_file_name(DISTRACTIONS_FILE)
+            .to_string_lossy()
+            .into_owned();
+        let projects = find_projects(&data_path);
+        let settings = load_settings();
+        let (keymap, mut warnings) = build_keymap(&settings.keybindings);
+        if let Some(template) = &settings.list_format {
+            let unknown = unknown_placeholders(template, &LIST_PLACEHOLDERS);
+            if !unknown.is_empty() {
+                warnings.push(format!(
+                    "Unknown list_format placeholders {} are shown as written.",
+                    unknown.join(", ")
+                ));
+            }
+        }
+        let mut app = Self {
+            todos: load_todos(&data_path, settings.long_break_every, clock.now()),
+            data_mtime: modified_at(&data_path),
+            data_path,
+            archive_path,
+            form: TaskForm::default(),
+            minutes_input: String::new(),
+            checklist_input: String::new(),
+            selected_index: 0,
+            input_mode: InputMode::NoTyping,
+            cursor_position: 0,
+            status_message: None,
+            view: View::Tasks,
+            focus_lock: false,
+            settings,
+            settings_index: 0,
+            skipped_breaks: 0,
+            pending_confirm: None,
+            last_activity: clock.now(),
+            snapshot_scroll: 0,
+            flash_until: None,
+            read_only: mode.read_only,
+            no_save: mode.no_save,
+            hooks: Vec::new(),
+            save_due: None,
+            quick_timer: None,
+            blur_paused: Vec::new(),
+            zen: false,
+            projects,
+            active_project: 0,
+            project_input: String::new(),
+            split_input: String::new(),
+            intent_input: String::new(),
+            import_input: String::new(),
+            command_input: String::new(),
+            pending_session: None,
+            log_path,
+            sort_descending: false,
+            last_language: None,
+            sessions_this_run: 0,
+            farewell: None,
+            archived: Vec::new(),
+            archive_index: 0,
+            distractions: load_distractions(&distractions_path),
+            distraction_input: String::new(),
+            distractions_path,
+            active_task: None,
+            focus_task: None,
+            error_banner: None,
+            controls_hint: controls_hint(&keymap),
+            key_help: key_help(&keymap),
+            keymap,
+            compact_list: false,
+            group_by_state: false,
+            task_sparkline: false,
+            debug: mode.debug,
+            clock,
+        };
+        app.check_readable();
+        app.reset_recurring();
+        if !warnings.is_empty() {
+            app.set_status(warnings.join(" "));
+        }
+        app
+    }
+
+    /// Runs an action from a key or the command palette, unless focus lock
+    /// or read-only mode rules it out. Returns true when the app should quit.
+    pub fn run_action(&mut self, action: Action) -> bool {
+        if action.breaks_focus() && self.is_focus_locked() {
+            self.set_status("Stay focused!");
+        } else if action.edits() && self.read_only {
+            self.set_status("Read-only mode: editing is disabled.");
+        } else {
+            return self.perform(action);
+        }
+        false
+    }
+
+    /// Runs the line typed into the command palette: any action name from
+    /// settings.txt, a short alias such as `add` or `export`, or `delete`.
+    /// Returns true when the app should quit.
+    pub fn confirm_command(&mut self) -> bool {
+        let command = self.command_input.trim().to_lowercase();
+        self.command_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        if command.is_empty() {
+            return false;
+        }
+        if command == "delete" {
+            if self.is_focus_locked() {
+                self.set_status("Stay focused!");
+            } else if self.read_only {
+                self.set_status("Read-only mode: editing is disabled.");
+            } else {
+                self.delete_selected();
+            }
+            return false;
+        }
+        match Action::from_command(&command) {
+            Some(action) => self.run_action(action),
+            None => {
+                self.set_status(format!(
+                    "Unknown command '{}'. Try add, delete, stats, export or any action name.",
+                    command
+                ));
+                false
+            }
+        }
+    }
+
+    pub fn delete_selected(&mut self) {
+        if self.todos.is_empty() {
+            return;
+        }
+        let removed = self.todos.remove(self.selected_index);
+        self.task_removed(self.selected_index);
+        self.clamp_selection();
+        self.save();
+        self.set_status(format!("Removed '{}'.", removed.name));
+    }
+
+    /// Runs the command bound to a key. Returns true when the app should quit.
+    pub fn perform(&mut self, action: Action) -> bool {
+        match action {
+            Action::Quit if self.has_running_timer() => {
+                self.pending_confirm = Some(Confirmation::Quit);
+            }
+            Action::Quit => {
+                self.save();
+                return true;
+            }
+            Action::TogglePomodoro => self.toggle_pomodoro(),
+            Action::StartSelected => self.toggle_selected_pomodoro(),
+            Action::PauseAll => self.pause_all(),
+            Action::AddTask => {
+                self.input_mode = InputMode::Form;
+                self.focus_form_field(FIELD_NAME);
+            }
+            Action::Duplicate => self.duplicate_selected(),
+            Action::Language => self.cycle_language(),
+            Action::RepeatLanguage => self.repeat_language(),
+            Action::Pin => self.toggle_pinned(),
+            Action::ToggleDone => self.toggle_done(),
+            Action::Reopen => self.reopen_task(),
+            Action::Recurring => self.toggle_recurring(),
+            Action::Label => self.cycle_label(),
+            Action::Sort => self.sort_by_name(),
+            Action::Interruption => self.log_interruption(),
+            Action::StopTimer => self.reset_timer(),
+            Action::MarkActive => self.toggle_active_task(),
+            Action::Export => self.export_summary(),
+            Action::OpenFolder => self.open_data_folder(),
+            Action::CopyName => self.copy_task_name(),
+            Action::ExtendSession => self.extend_session(),
+            Action::ResetStats => self.pending_confirm = Some(Confirmation::ResetStats),
+            Action::Archive => self.archive_task(self.selected_index),
+            Action::Suggest => self.suggest_task(),
+            Action::ClearMessage => self.status_message = None,
+            Action::SkipBreak => self.skip_break(),
+            Action::Stopwatch => self.toggle_stopwatch(),
+            Action::QuickTimer => self.toggle_quick_timer(),
+            Action::Zen => self.zen = !self.zen,
+            Action::CompactList => self.compact_list = !self.compact_list,
+            Action::GroupList => self.group_by_state = !self.group_by_state,
+            Action::Theme => {
+                self.settings.theme = self.settings.theme.cycle(true);
+                save_settings(&self.settings);
+            }
+            Action::HideSeconds => {
+                self.settings.hide_seconds = !self.settings.hide_seconds;
+                save_settings(&self.settings);
+            }
+            Action::TaskHistory => self.task_sparkline = !self.task_sparkline,
+            Action::IncreaseCount => self.adjust_completed(true),
+            Action::DecreaseCount => self.adjust_completed(false),
+            Action::FocusLock => self.toggle_focus_lock(),
+            Action::Stats => {
+                self.view = match self.view {
+                    View::Stats => View::Tasks,
+                    _ => {
+                        self.archived = load_todos(
+                            &self.archive_path,
+                            self.settings.long_break_every,
+                            self.clock.now(),
+                        );
+                        View::Stats
+                    }
+                };
+            }
+            Action::Jot => {
+                self.input_mode = InputMode::Distraction;
+                self.cursor_position = 0;
+            }
+            Action::Distractions => {
+                self.view = match self.view {
+                    View::Distractions => View::Tasks,
+                    _ => View::Distractions,
+                };
+            }
+            Action::Help => {
+                self.view = match self.view {
+                    View::Help => View::Tasks,
+                    _ => View::Help,
+                };
+            }
+            Action::Archived => {
+                self.view = match self.view {
+                    View::Archive => View::Tasks,
+                    _ => {
+                        self.archived = load_todos(
+                            &self.archive_path,
+                            self.settings.long_break_every,
+                            self.clock.now(),
+                        );
+                        self.archive_index = 0;
+                        View::Archive
+                    }
+                };
+            }
+            Action::OpenSettings => {
+                self.view = match self.view {
+                    View::Settings => View::Tasks,
+                    _ => View::Settings,
+                };
+            }
+            Action::AddChecklistItem if !self.todos.is_empty() => {
+                self.input_mode = InputMode::Checklist;
+                self.cursor_position = 0;
+            }
+            Action::NewProject => {
+                self.input_mode = InputMode::Project;
+                self.cursor_position = 0;
+            }
+            Action::Import => {
+                self.input_mode = InputMode::Import;
+                self.cursor_position = 0;
+            }
+            Action::Command => {
+                self.input_mode = InputMode::Command;
+                self.cursor_position = 0;
+            }
+            Action::Split if !self.todos.is_empty() => {
+                self.input_mode = InputMode::Split;
+                self.cursor_position = 0;
+            }
+            Action::CustomMinutes if !self.todos.is_empty() => {
+                self.input_mode = InputMode::Minutes;
+                self.cursor_position = 0;
+            }
+            Action::AddChecklistItem | Action::CustomMinutes | Action::Split => {}
+        }
+        false
+    }
+
+    /// Keeps `selected_index` inside the list after tasks disappear, or at 0
+    /// when the list is empty.
+    pub fn clamp_selection(&mut self) {
+        self.selected_index = self.selected_index.min(self.todos.len().saturating_sub(1));
+    }
+
+    /// The list in drawing order. Without grouping that is just `todos`;
+    /// with it, each non-empty group gets a header followed by its tasks.
+    pub fn list_rows(&self) -> Vec<ListRow> {
+        if !self.group_by_state {
+            return (0..self.todos.len()).map(ListRow::Task).collect();
+        }
+        let mut rows = Vec::new();
+        for (group, header) in GROUP_HEADERS.into_iter().enumerate() {
+            let members: Vec<usize> = (0..self.todos.len())
+                .filter(|&i| state_group(&self.todos[i]) == group)
+                .collect();
+            if !members.is_empty() {
+                rows.push(ListRow::Header(header));
+                rows.extend(members.into_iter().map(ListRow::Task));
+            }
+        }
+        rows
+    }
+
+    /// Selects the next (or previous) task as drawn, passing over headers
+    /// and stopping at either end.
+    pub fn move_selection(&mut self, down: bool) {
+        let order: Vec<usize> = self
+            .list_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                ListRow::Task(i) => Some(i),
+                ListRow::Header(_) => None,
+            })
+            .collect();
+        let Some(position) = order.iter().position(|&i| i == self.selected_index) else {
+            return;
+        };
+        let next = if down {
+            position.checked_add(1)
+        } else {
+            position.checked_sub(1)
+        };
+        if let Some(&index) = next.and_then(|next| order.get(next)) {
+            self.selected_index = index;
+        }
+    }
+
+    /// The task timer keys act on: the active task if one is set, then the
+    /// task whose timer is going, otherwise the selection.
+    pub fn timer_index(&self) -> usize {
+        let has_timer = |i: &usize| {
+            self.todos
+                .get(*i)
+                .is_some_and(|task| task.pomodoro_start.is_some() || task.paused.is_some())
+        };
+        self.active_task
+            .filter(|&i| i < self.todos.len())
+            .or(self.focus_task.filter(has_timer))
+            .unwrap_or(self.selected_index)
+    }
+
+    /// Starts, pauses or resumes the highlighted task's timer even while
+    /// another task's timer has the gauge, and hands the gauge to it.
+    fn toggle_selected_pomodoro(&mut self) {
+        if self.selected_index >= self.todos.len() {
+            return;
+        }
+        if self
+            .active_task
+            .is_some_and(|active| active != self.selected_index)
+        {
+            self.active_task = Some(self.selected_index);
+        }
+        self.focus_task = Some(self.selected_index);
+        self.toggle_pomodoro();
+    }
+
+    /// Marks the selected task as the one being worked on, or clears the
+    /// mark if it already is.
+    fn toggle_active_task(&mut self) {
+        let Some(task) = self.todos.get(self.selected_index) else {
+            return;
+        };
+        if self.active_task == Some(self.selected_index) {
+            self.active_task = None;
+            self.set_status("Active task cleared; p follows the running timer or the selection.");
+        } else {
+            let message = format!("'{}' is now the active task.", task.name);
+            self.active_task = Some(self.selected_index);
+            self.set_status(message);
+        }
+    }
+
+    /// Keeps `active_task` and `focus_task` pointing at the same tasks after
+    /// one is removed.
+    pub fn task_removed(&mut self, index: usize) {
+        for marked in [&mut self.active_task, &mut self.focus_task] {
+            *marked = match *marked {
+                Some(i) if i == index => None,
+                Some(i) if i > index => Some(i - 1),
+                other => other,
+            };
+        }
+    }
+
+    /// Keeps `active_task` and `focus_task` pointing at the same tasks after
+    /// one is inserted.
+    fn task_inserted(&mut self, index: usize) {
+        for marked in [&mut self.active_task, &mut self.focus_task] {
+            if let Some(i) = marked.as_mut() {
+                if *i >= index {
+                    *i += 1;
+                }
+            }
+        }
+    }
+
+    fn has_running_timer(&self) -> bool {
+        self.quick_timer.is_some() || self.todos.iter().any(|task| task.pomodoro_start.is_some())
+    }
+
+    /// Starts or cancels the standalone timer that isn't tied to a task.
+    fn toggle_quick_timer(&mut self) {
+        if self.quick_timer.take().is_some() {
+            self.set_status("Quick timer cancelled.");
+        } else {
+            self.quick_timer = Some(self.clock.now());
+            self.set_status(format!(
+                "Quick {}-minute timer started. Press T to cancel.",
+                WORK_DURATION.as_secs() / 60
+            ));
+        }
+    }
+
+    pub fn is_typing(&self) -> bool {
+        !matches!(self.input_mode, InputMode::NoTyping)
+    }
+
+    /// List editing is frozen while focus lock is on and a work session runs.
+    /// Breaks are left unlocked.
+    pub fn is_focus_locked(&self) -> bool {
+        self.focus_lock
+            && self.todos.iter().any(|task| {
+                matches!(
+                    task.pomodoro_state,
+                    PomodoroState::Work | PomodoroState::Stopwatch
+                )
+            })
+    }
+
+    /// Changes the highlighted row on the settings screen and previews the
+    /// resulting alert.
+    pub fn adjust_setting(&mut self, forward: bool) {
+        self.settings.adjust(self.settings_index, forward);
+        save_settings(&self.settings);
+        play_alert(&self.settings);
+    }
+
+    fn toggle_focus_lock(&mut self) {
+        self.focus_lock = !self.focus_lock;
+        let message = if self.focus_lock {
+            "Focus lock on: the list is frozen during work sessions."
+        } else {
+            "Focus lock off."
+        };
+        self.set_status(message.to_string());
+    }
+
+    /// Starts a work session on the selected task. `custom` overrides the
+    /// work length for this session only.
+    /// Asks first if the task is already done, since time logged against a
+    /// finished task is usually a mistake.
+    fn start_pomodoro(&mut self, custom: Option<Duration>) {
+        if self
+            .todos
+            .get(self.timer_index())
+            .is_some_and(|task| task.done)
+        {
+            self.pending_confirm = Some(Confirmation::StartDone(custom));
+            return;
+        }
+        self.begin_work(custom);
+    }
+
+    /// Asks for the session's intent first when that is turned on.
+    pub fn begin_work(&mut self, custom: Option<Duration>) {
+        let Some(task) = self.todos.get(self.timer_index()) else {
+            return;
+        };
+        if let PomodoroState::Stopwatch = task.pomodoro_state {
+            self.set_status("Stop the stopwatch first (t).");
+            return;
+        }
+        if self.settings.ask_intent {
+            self.pending_session = custom;
+            self.input_mode = InputMode::Intent;
+            self.cursor_position = 0;
+            return;
+        }
+        self.start_work(custom, None);
+    }
+
+    /// Starts the session once its intent, if any, has been typed. An empty
+    /// answer starts it without one.
+    pub fn confirm_intent(&mut self) {
+        let intent = field_value(&self.intent_input);
+        self.intent_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        let custom = self.pending_session.take();
+        self.start_work(custom, Some(intent).filter(|i| !i.is_empty()));
+    }
+
+    fn start_work(&mut self, custom: Option<Duration>, intent: Option<String>) {
+        let index = self.timer_index();
+        let Some(task) = self.todos.get_mut(index) else {
+            return;
+        };
+        task.intent = intent;
+        task.pomodoro_state = PomodoroState::Work;
+        task.pomodoro_start = Some(self.clock.now());
+        task.paused = None;
+        task.session_duration = custom;
+        task.extensions = 0;
+        task.last_worked = Some(SystemTime::now());
+        self.focus_task = Some(index);
+        let message = format!(
+            "Started {}-minute focus on '{}'. Stay sharp!",
+            task.work_duration().as_secs() / 60,
+            task.name
+        );
+        self.set_status(message);
+        self.save();
+        self.run_hook(self.settings.on_work_start.clone());
+    }
+
+    /// Spawns a configured hook through the shell without waiting for it.
+    fn run_hook(&mut self, command: Option<String>) {
+        let Some(command) = command else {
+            return;
+        };
+        match Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => self.hooks.push((command, child)),
+            Err(err) => self.set_status(format!("Couldn't run '{}': {}", command, err)),
+        }
+    }
+
+    /// Reaps finished hooks and reports the ones that failed.
+    pub fn check_hooks(&mut self) {
+        let mut failed = None;
+        self.hooks
+            .retain_mut(|(command, child)| match child.try_wait() {
+                Ok(None) => true,
+                Ok(Some(status)) => {
+                    if status.code() == Some(127) {
+                        failed = Some(format!("Command not found: '{}'", command));
+                    } else if !status.success() {
+                        failed = Some(format!("'{}' failed ({})", command, status));
+                    }
+                    false
+                }
+                Err(_) => false,
+            });
+        if let Some(message) = failed {
+            self.set_status(message);
+        }
+    }
+
+    /// Shows the folder holding the task list in the file manager, e.g. to
+    /// get at backups. The child is reaped with the hooks.
+    fn open_data_folder(&mut self) {
+        let dir = match Path::new(&self.data_path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        match Command::new(FILE_MANAGER)
+            .arg(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => {
+                let command = format!("{} {}", FILE_MANAGER, dir.display());
+                self.hooks.push((command, child));
+                self.set_status(format!("Opened {} in the file manager.", dir.display()));
+            }
+            Err(err) => self.set_status(format!("Couldn't run {}: {}", FILE_MANAGER, err)),
+        }
+    }
+
+    /// Puts the selected task's name on the clipboard through the first
+    /// clipboard command that is installed. The child is reaped with the
+    /// hooks, so a tool that can't reach a display reports its failure there.
+    fn copy_task_name(&mut self) {
+        let Some(task) = self.todos.get(self.selected_index) else {
+            return;
+        };
+        let name = task.name.clone();
+        for command in CLIPBOARD_COMMANDS {
+            let spawned = Command::new(command[0])
+                .args(&command[1..])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            let Ok(mut child) = spawned else {
+                continue;
+            };
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(name.as_bytes());
+            }
+            self.hooks.push((command.join(" "), child));
+            self.set_status(format!("Copied '{}' to the clipboard.", name));
+            return;
+        }
+        let tools: Vec<&str> = CLIPBOARD_COMMANDS
+            .iter()
+            .map(|command| command[0])
+            .collect();
+        self.set_status(format!(
+            "No clipboard available (tried {}).",
+            tools.join(", ")
+        ));
+    }
+
+    fn focus_form_field(&mut self, field: usize) {
+        self.form.focused = field;
+        self.cursor_position = self.form.values[field].chars().count();
+    }
+
+    pub fn next_form_field(&mut self) {
+        self.form.next_field();
+        self.focus_form_field(self.form.focused);
+    }
+
+    pub fn prev_form_field(&mut self) {
+        self.form.prev_field();
+        self.focus_form_field(self.form.focused);
+    }
+
+    /// Validates the whole form, jumping to the first field that is wrong.
+    pub fn confirm_new_task(&mut self) {
+        let name = field_value(&self.form.values[FIELD_NAME]);
+        if name.is_empty() {
+            self.focus_form_field(FIELD_NAME);
+            self.set_status("Task name can't be blank.");
+            return;
+        }
+        let language = field_value(&self.form.values[FIELD_LANGUAGE]);
+        if language.is_empty() {
+            self.focus_form_field(FIELD_LANGUAGE);
+            self.set_status("Language can't be blank.");
+            return;
+        }
+        let Some(priority) = Priority::parse(&self.form.values[FIELD_PRIORITY]) else {
+            self.focus_form_field(FIELD_PRIORITY);
+            self.set_status("Priority must be high, normal or low.");
+            return;
+        };
+        let estimate = self.form.values[FIELD_ESTIMATE].trim();
+        let estimated_pomodoros = if estimate.is_empty() {
+            None
+        } else {
+            match estimate.parse::<u32>() {
+                Ok(count) if count > 0 => Some(count),
+                _ => {
+                    self.focus_form_field(FIELD_ESTIMATE);
+                    self.set_status("Estimate must be a whole number of pomodoros.");
+                    return;
+                }
+            }
+        };
+        if self
+            .todos
+            .iter()
+            .any(|task| task.name.eq_ignore_ascii_case(&name))
+        {
+            self.focus_form_field(FIELD_NAME);
+            self.set_status(format!("A task named '{}' already exists.", name));
+            return;
+        }
+
+        self.last_language = Some(language.clone());
+        let mut task = Task::new(name, language);
+        task.priority = priority;
+        task.estimated_pomodoros = estimated_pomodoros;
+        self.todos.push(task);
+        self.save();
+        self.form = TaskForm::default();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        self.set_status("New task added. Ready to focus!");
+    }
+
+    /// Saves the current project and loads the one at `index` in its place.
+    fn switch_project(&mut self, index: usize) {
+        if index == self.active_project || index >= self.projects.len() {
+            return;
+        }
+        self.save();
+        self.active_project = index;
+        let project = &self.projects[index];
+        self.data_path = project.path.clone();
+        self.archive_path = archive_path_for(&project.path);
+        self.todos = load_todos(
+            &project.path,
+            self.settings.long_break_every,
+            self.clock.now(),
+        );
+        self.data_mtime = modified_at(&project.path);
+        self.selected_index = 0;
+        self.active_task = None;
+        self.focus_task = None;
+        self.snapshot_scroll = 0;
+        self.blur_paused.clear();
+        let message = format!("Switched to project '{}'.", project.name);
+        self.set_status(message);
+        self.check_readable();
+        self.reset_recurring();
+    }
+
+    pub fn next_project(&mut self) {
+        if self.projects.len() < 2 {
+            self.set_status("Only one project. Press N to create another.");
+            return;
+        }
+        self.switch_project((self.active_project + 1) % self.projects.len());
+    }
+
+    /// Creates an empty list for the typed project name and switches to it.
+    pub fn confirm_new_project(&mut self) {
+        let name = collapse_whitespace(&self.project_input);
+        if name.is_empty() {
+            self.set_status("Project name can't be blank.");
+            return;
+        }
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            self.set_status("Project names can't contain slashes or start with a dot.");
+            return;
+        }
+        if is_archive_file(&format!("{}.txt", name)) {
+            self.set_status("That name is reserved for archives.");
+            return;
+        }
+        if self
+            .projects
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(&name))
+        {
+            self.set_status(format!("A project named '{}' already exists.", name));
+            return;
+        }
+
+        let dir = Path::new(&self.projects[0].path).with_file_name(PROJECTS_DIR);
+        let path = dir
+            .join(format!("{}.txt", name))
+            .to_string_lossy()
+            .into_owned();
+        // A dry run keeps the new project in memory only.
+        let created = if self.no_save {
+            Ok(())
+        } else {
+            fs::create_dir_all(&dir)
+        };
+        if let Err(err) = created.and_then(|_| self.write_tasks(&path, &[], 0)) {
+            self.set_persistent_status(format!("Could not create project: {}", err));
+            return;
+        }
+        self.project_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        self.projects.push(Project { name, path });
+        self.switch_project(self.projects.len() - 1);
+    }
+
+    /// Files the typed note away and returns to whatever was running.
+    pub fn confirm_distraction(&mut self) {
+        let note = collapse_whitespace(&self.distraction_input);
+        self.distraction_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        if note.is_empty() {
+            return;
+        }
+        self.distractions.push(note);
+        self.save_distractions();
+        self.set_status(format!(
+            "Noted for later ({} waiting). Back to work!",
+            self.distractions.len()
+        ));
+    }
+
+    pub fn clear_distractions(&mut self) {
+        let count = self.distractions.len();
+        self.distractions.clear();
+        self.save_distractions();
+        self.set_status(format!("Cleared {} distractions.", count));
+    }
+
+    fn save_distractions(&mut self) {
+        let content: String = self
+            .distractions
+            .iter()
+            .map(|note| format!("{}\n", note))
+            .collect();
+        if let Err(err) = fs::write(&self.distractions_path, content) {
+            self.set_persistent_status(format!(
+                "Could not save {}: {}",
+                self.distractions_path, err
+            ));
+        }
+    }
+
+    pub fn confirm_checklist_item(&mut self) {
+        // `;` separates the items within the checklist field.
+        let item = field_value(&self.checklist_input).replace(';', ",");
+        if item.is_empty() {
+            return;
+        }
+        self.checklist_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        if let Some(task) = self.todos.get_mut(self.selected_index) {
+            task.checklist.push((item, false));
+            self.save();
+        }
+    }
+
+    /// Flips the checklist item shown as `number` (1-based) on the selected task.
+    pub fn toggle_checklist_item(&mut self, number: usize) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        let Some((item, done)) = number
+            .checked_sub(1)
+            .and_then(|i| task.checklist.get_mut(i))
+        else {
+            return;
+        };
+        *done = !*done;
+        let message = if *done {
+            format!("Checked off '{}'.", item)
+        } else {
+            format!("Reopened '{}'.", item)
+        };
+        self.set_status(message);
+        self.save();
+    }
+
+    pub fn confirm_custom_minutes(&mut self) {
+        match self.minutes_input.parse::<u64>() {
+            Ok(minutes) if (1..=MAX_CUSTOM_MINUTES).contains(&minutes) => {
+                self.minutes_input.clear();
+                self.input_mode = InputMode::NoTyping;
+                self.cursor_position = 0;
+                self.start_pomodoro(Some(Duration::from_secs(minutes * 60)));
+            }
+            _ => {
+                self.set_status(format!(
+                    "Enter between 1 and {} minutes.",
+                    MAX_CUSTOM_MINUTES
+                ));
+            }
+        }
+    }
+
+    /// Advances every task with a running timer, whichever one is selected.
+    pub fn update_pomodoro(&mut self) {
+        if self
+            .quick_timer
+            .is_some_and(|start| self.clock.since(start) >= WORK_DURATION)
+        {
+            self.quick_timer = None;
+            self.set_status("Quick timer finished!");
+            play_alert(&self.settings);
+        }
+        for index in 0..self.todos.len() {
+            let task = &mut self.todos[index];
+            let Some(start) = task.pomodoro_start else {
+                continue;
+            };
+            let elapsed = self.clock.since(start);
+            let mut work_ended = false;
+            let mut log_error = None;
+
+            let message = match task.pomodoro_state {
+                PomodoroState::Work if elapsed >= task.work_duration() => {
+                    task.history.push(Session {
+                        finished_at: SystemTime::now(),
+                        duration: task.work_duration(),
+                    });
+                    if self.settings.log_sessions && !self.no_save {
+                        log_error = log_session(&self.log_path, task, task.work_duration()).err();
+                    }
+                    task.intent = None;
+                    task.total_focus_time += task.work_duration();
+                    task.pomodoro_state = PomodoroState::Break;
+                    task.pomodoro_start = Some(self.clock.now());
+                    task.session_duration = None;
+                    task.extensions = 0;
+                    task.completed_pomodoros += 1;
+                    self.flash_until = Some(self.clock.now() + FLASH_DURATION);
+                    work_ended = true;
+                    self.sessions_this_run += 1;
+                    let kind = if task.is_long_break(self.settings.long_break_every) {
+                        "a long"
+                    } else {
+                        "a"
+                    };
+                    // Only on the session that hits the estimate, so a task
+                    // reopened without resetting its count stays open.
+                    let target = task.estimated_pomodoros.filter(|&estimate| {
+                        self.settings.auto_complete
+                            && !task.done
+                            && task.completed_pomodoros == estimate
+                    });
+                    if let Some(target) = target {
+                        task.done = true;
+                        Some(format!(
+                            "🎉 '{}' hit its target of {} pomodoros and is done! Take {} break.",
+                            task.name, target, kind
+                        ))
+                    } else {
+                        Some(format!(
+                            "Work session done! Take {} break, {}.",
+                            kind, task.name
+                        ))
+                    }
+                }
+                PomodoroState::Break
+                    if elapsed >= task.break_duration(self.settings.long_break_every) =>
+                {
+                    task.total_break_time += task.break_duration(self.settings.long_break_every);
+                    task.pomodoro_state = PomodoroState::Idle;
+                    task.pomodoro_start = None;
+                    self.skipped_breaks = 0;
+                    Some(format!(
+                        "Break over for '{}'. Ready for another round?",
+                        task.name
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some(message) = message {
+                self.set_status(message);
+                play_alert(&self.settings);
+                self.request_save();
+            }
+            if work_ended {
+                self.run_hook(self.settings.on_work_end.clone());
+                if self
+                    .settings
+                    .auto_exit_after
+                    .is_some_and(|target| self.sessions_this_run >= target)
+                {
+                    self.farewell = Some(format!(
+                        "Sprint complete: {} pomodoros this run. Nice work — go rest!",
+                        self.sessions_this_run
+                    ));
+                }
+            }
+            if let Some(err) = log_error {
+                self.set_status(format!("Could not write {}: {}", self.log_path, err));
+            }
+        }
+    }
+
+    /// Starts counting up on the selected task, or stops the running
+    /// stopwatch and adds its time to the task's focus total.
+    fn toggle_stopwatch(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        let message = match (&task.pomodoro_state, task.elapsed(self.clock.now())) {
+            (PomodoroState::Stopwatch, Some(elapsed)) => {
+                task.total_focus_time += elapsed;
+                task.stop_timer();
+                format!(
+                    "Stopwatch stopped: {} added to '{}'.",
+                    format_hours_minutes(elapsed),
+                    task.name
+                )
+            }
+            (PomodoroState::Idle, _) => {
+                task.pomodoro_state = PomodoroState::Stopwatch;
+                task.pomodoro_start = Some(self.clock.now());
+                task.last_worked = Some(SystemTime::now());
+                format!("Stopwatch running for '{}'. Press t to stop.", task.name)
+            }
+            _ => "A pomodoro is already running on this task.".to_string(),
+        };
+        if matches!(task.pomodoro_state, PomodoroState::Stopwatch) {
+            self.focus_task = Some(self.selected_index);
+        }
+        self.set_status(message);
+        self.save();
+    }
+
+    /// Pauses the selected task's running phase, resumes it if paused, and
+    /// otherwise starts a new work session.
+    fn toggle_pomodoro(&mut self) {
+        let index = self.timer_index();
+        let Some(task) = self.todos.get_mut(index) else {
+            return;
+        };
+        if task.paused.is_some() {
+            task.resume(self.clock.now());
+            let message = format!("Resumed '{}'.", task.name);
+            self.set_status(message);
+        } else if task.pomodoro_start.is_some() {
+            task.pause(self.clock.now());
+            let message = format!("Paused '{}'. Press p to resume.", task.name);
+            self.set_status(message);
+        } else {
+            self.start_pomodoro(None);
+            return;
+        }
+        self.save();
+    }
+
+    /// Pauses every running timer, wherever the selection is, for when
+    /// something needs attention right now.
+    fn pause_all(&mut self) {
+        let now = self.clock.now();
+        let mut paused: Vec<&str> = Vec::new();
+        for task in &mut self.todos {
+            if task.pomodoro_start.is_some() {
+                task.pause(now);
+                paused.push(&task.name);
+            }
+        }
+        let message = match paused.as_slice() {
+            [] => "No timer running to pause.".to_string(),
+            [name] => format!("Paused '{}'. Press p on it to resume.", name),
+            names => format!(
+                "Paused {} timers: {}. Press p on each to resume.",
+                names.len(),
+                names.join(", ")
+            ),
+        };
+        let any = !paused.is_empty();
+        self.set_status(message);
+        if any {
+            self.save();
+        }
+    }
+
+    /// Backdates the running work session or break so that the next
+    /// `update_pomodoro` completes it, for trying out the transitions.
+    pub fn finish_phase_now(&mut self) {
+        let index = self.timer_index();
+        let Some(task) = self.todos.get_mut(index) else {
+            return;
+        };
+        let length = match task.pomodoro_state {
+            PomodoroState::Work => task.work_duration(),
+            PomodoroState::Break => task.break_duration(self.settings.long_break_every),
+            PomodoroState::Idle | PomodoroState::Stopwatch => {
+                self.set_status("Debug: no work session or break to finish.");
+                return;
+            }
+        };
+        task.paused = None;
+        let now = self.clock.now();
+        task.pomodoro_start = Some(now.checked_sub(length).unwrap_or(now));
+    }
+
+    /// Pauses every running work session or stopwatch and returns their indices.
+    fn pause_focus_timers(&mut self) -> Vec<usize> {
+        let mut paused = Vec::new();
+        for (index, task) in self.todos.iter_mut().enumerate() {
+            let focusing = matches!(
+                task.pomodoro_state,
+                PomodoroState::Work | PomodoroState::Stopwatch
+            );
+            if focusing && task.pomodoro_start.is_some() {
+                task.pause(self.clock.now());
+                paused.push(index);
+            }
+        }
+        paused
+    }
+
+    /// Pauses focus timers while the terminal is in the background, if enabled.
+    pub fn focus_lost(&mut self) {
+        if !self.settings.pause_on_focus_loss {
+            return;
+        }
+        self.blur_paused = self.pause_focus_timers();
+        if !self.blur_paused.is_empty() {
+            self.save();
+        }
+    }
+
+    /// Resumes the timers that `focus_lost` paused.
+    pub fn focus_gained(&mut self) {
+        let indices = std::mem::take(&mut self.blur_paused);
+        if indices.is_empty() {
+            return;
+        }
+        for index in indices {
+            if let Some(task) = self.todos.get_mut(index) {
+                task.resume(self.clock.now());
+            }
+        }
+        self.set_status("Welcome back — timers resumed.");
+        self.save();
+    }
+
+    /// Pauses every running work session or stopwatch once no key has been
+    /// pressed for the configured idle timeout.
+    pub fn check_idle(&mut self) {
+        let Some(timeout) = self.settings.idle_timeout else {
+            return;
+        };
+        if self.clock.since(self.last_activity) < timeout {
+            return;
+        }
+
+        if !self.pause_focus_timers().is_empty() {
+            self.set_persistent_status(format!(
+                "Paused after {} minutes without input. Press p to resume.",
+                timeout.as_secs() / 60
+            ));
+            self.save();
+        }
+    }
+
+    /// Stops the selected task's work session or break without completing
+    /// it. Time already spent working can be credited, per settings.
+    fn reset_timer(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        let (state, Some(elapsed)) = (&task.pomodoro_state, task.elapsed(self.clock.now())) else {
+            self.set_status("No timer running on this task.");
+            return;
+        };
+        let message = match state {
+            PomodoroState::Work if self.settings.credit_partial => {
+                task.total_focus_time += elapsed;
+                format!(
+                    "Stopped '{}' early; {} credited to focus time.",
+                    task.name,
+                    format_hours_minutes(elapsed)
+                )
+            }
+            PomodoroState::Work => format!("Stopped '{}' early.", task.name),
+            PomodoroState::Break => format!("Ended the break for '{}'.", task.name),
+            _ => {
+                self.set_status("Use t to stop the stopwatch.");
+                return;
+            }
+        };
+        let was_working = matches!(state, PomodoroState::Work);
+        task.stop_timer();
+        self.save();
+        self.set_status(message);
+        if was_working {
+            self.run_hook(self.settings.on_work_end.clone());
+        }
+    }
+
+    /// Adds five minutes to the selected task's work session, a few times
+    /// at most so a pomodoro keeps meaning something.
+    fn extend_session(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        if !matches!(task.pomodoro_state, PomodoroState::Work) {
+            self.set_status("Only a running work session can be extended.");
+            return;
+        }
+        if task.extensions >= MAX_EXTENSIONS {
+            self.set_status(format!(
+                "Already extended {} times — time for a break.",
+                MAX_EXTENSIONS
+            ));
+            return;
+        }
+        task.session_duration = Some(task.work_duration() + EXTENSION);
+        task.extensions += 1;
+        let message = format!(
+            "Extended '{}' by {} minutes ({}/{}).",
+            task.name,
+            EXTENSION.as_secs() / 60,
+            task.extensions,
+            MAX_EXTENSIONS
+        );
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Counts an interruption against whichever task has a work session
+    /// running, preferring the selected one.
+    fn log_interruption(&mut self) {
+        let running = |task: &Task| {
+            matches!(task.pomodoro_state, PomodoroState::Work) && task.pomodoro_start.is_some()
+        };
+        let index = match self.todos.get(self.selected_index) {
+            Some(task) if running(task) => Some(self.selected_index),
+            _ => self.todos.iter().position(running),
+        };
+        let Some(task) = index.map(|i| &mut self.todos[i]) else {
+            self.set_status("No work session running to interrupt.");
+            return;
+        };
+        task.interruptions += 1;
+        let message = format!(
+            "Interruption logged on '{}' ({} so far). Back to it!",
+            task.name, task.interruptions
+        );
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Zeroes every task's counts and focus time and drops session history.
+    pub fn reset_stats(&mut self) {
+        let pomodoros: u32 = self.todos.iter().map(|t| t.completed_pomodoros).sum();
+        let focus: Duration = self.todos.iter().map(|t| t.total_focus_time).sum();
+        for task in &mut self.todos {
+            task.completed_pomodoros = 0;
+            task.total_focus_time = Duration::ZERO;
+            task.total_break_time = Duration::ZERO;
+            task.history.clear();
+        }
+        self.save();
+        self.set_status(format!(
+            "Reset {} tasks: cleared {} pomodoros and {} of focus time.",
+            self.todos.len(),
+            pomodoros,
+            format_hours_minutes(focus)
+        ));
+    }
+
+    /// Manually credits or removes a completed pomodoro, never below zero.
+    fn adjust_completed(&mut self, increase: bool) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.completed_pomodoros = if increase {
+            task.completed_pomodoros.saturating_add(1)
+        } else {
+            task.completed_pomodoros.saturating_sub(1)
+        };
+        let message = format!(
+            "'{}' now has {} completed pomodoros.",
+            task.name, task.completed_pomodoros
+        );
+        self.set_status(message);
+        self.save();
+    }
+
+    /// Ends the selected task's break early. Skipping too many in a row
+    /// triggers a persistent reminder to rest.
+    fn skip_break(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        if !matches!(task.pomodoro_state, PomodoroState::Break) {
+            self.set_status("No break to skip.");
+            return;
+        }
+        task.stop_timer();
+        self.skipped_breaks += 1;
+
+        if self.skipped_breaks >= SKIPPED_BREAKS_WARNING {
+            self.set_persistent_status(format!(
+                "You've skipped {} breaks in a row. Please take a real rest! (c to dismiss)",
+                self.skipped_breaks
+            ));
+        } else {
+            self.set_status("Break skipped.");
+        }
+        self.save();
+    }
+
+    fn toggle_done(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.done = !task.done;
+        let message = if task.done {
+            format!("Marked '{}' as done.", task.name)
+        } else {
+            format!("Reopened '{}'.", task.name)
+        };
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Takes the selected task off done so more work can go into it, and
+    /// offers to restart its count (and so its long-break cycle).
+    fn reopen_task(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        if !task.done && task.completed_pomodoros == 0 {
+            let message = format!("'{}' is already open.", task.name);
+            self.set_status(message);
+            return;
+        }
+        task.done = false;
+        let message = format!("'{}' is active again.", task.name);
+        let counted = task.completed_pomodoros > 0;
+        self.save();
+        self.set_status(message);
+        if counted {
+            self.pending_confirm = Some(Confirmation::RestartCycle);
+        }
+    }
+
+    /// Moves the selected task's count into its lifetime total.
+    pub fn restart_cycle(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.lifetime_pomodoros += task.completed_pomodoros;
+        task.completed_pomodoros = 0;
+        let message = format!(
+            "'{}' is active again with a fresh count ({} pomodoros in its lifetime).",
+            task.name, task.lifetime_pomodoros
+        );
+        self.save();
+        self.set_status(message);
+    }
+
+    fn toggle_recurring(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.recurring = !task.recurring;
+        // Count today as already reset so the next start keeps today's work.
+        task.reset_at = task.recurring.then(SystemTime::now);
+        let message = if task.recurring {
+            format!("'{}' now resets every day.", task.name)
+        } else {
+            format!("'{}' no longer recurs.", task.name)
+        };
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Gives recurring tasks from an earlier day a fresh count and clears
+    /// their done flag. Their history and focus time are kept.
+    fn reset_recurring(&mut self) {
+        let now = SystemTime::now();
+        let today = local_day(now);
+        let mut reset = 0;
+        for task in self.todos.iter_mut().filter(|task| task.recurring) {
+            if local_day(task.reset_at.unwrap_or(task.created_at)) < today {
+                task.lifetime_pomodoros += task.completed_pomodoros;
+                task.completed_pomodoros = 0;
+                task.done = false;
+                task.reset_at = Some(now);
+                reset += 1;
+            }
+        }
+        if reset > 0 {
+            self.save();
+            self.set_status(format!("Reset {} recurring tasks for today.", reset));
+        }
+    }
+
+    fn toggle_pinned(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.pinned = !task.pinned;
+        let message = if task.pinned {
+            format!("Pinned '{}' to the top.", task.name)
+        } else {
+            format!("Unpinned '{}'.", task.name)
+        };
+        self.sort_pinned();
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Steps the selected task's label through the palette, then back to none.
+    fn cycle_label(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.label = match task.label.and_then(|l| LABELS.iter().position(|x| *x == l)) {
+            Some(i) => LABELS.get(i + 1).copied(),
+            None => Some(LABELS[0]),
+        };
+        self.save();
+    }
+
+    /// Moves pinned tasks above unpinned ones, keeping the order within each
+    /// group and the selection on the same task.
+    fn sort_pinned(&mut self) {
+        self.reorder_by(|a, b| b.pinned.cmp(&a.pinned));
+    }
+
+    /// Sorts by name ignoring case, alternating between A→Z and Z→A on each
+    /// call. Pinned tasks stay on top.
+    fn sort_by_name(&mut self) {
+        let descending = self.sort_descending;
+        self.reorder_by(|a, b| {
+            let order = a.name.to_lowercase().cmp(&b.name.to_lowercase());
+            if descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        self.sort_pinned();
+        self.sort_descending = !descending;
+        self.save();
+        self.set_status(if descending {
+            "Sorted Z→A. Press A again for A→Z."
+        } else {
+            "Sorted A→Z. Press A again for Z→A."
+        });
+    }
+
+    /// Stable-sorts the list while keeping the same task selected.
+    fn reorder_by(&mut self, mut compare: impl FnMut(&Task, &Task) -> Ordering) {
+        let mut indexed: Vec<(usize, Task)> = self.todos.drain(..).enumerate().collect();
+        indexed.sort_by(|(_, a), (_, b)| compare(a, b));
+        self.selected_index = indexed
+            .iter()
+            .position(|(i, _)| *i == self.selected_index)
+            .unwrap_or(0);
+        self.active_task = self
+            .active_task
+            .and_then(|active| indexed.iter().position(|(i, _)| *i == active));
+        self.focus_task = self
+            .focus_task
+            .and_then(|focus| indexed.iter().position(|(i, _)| *i == focus));
+        self.todos = indexed.into_iter().map(|(_, task)| task).collect();
+    }
+
+    /// Moves the selected task to the next preset language, wrapping around.
+    /// A language outside the list starts again from the first preset.
+    fn cycle_language(&mut self) {
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        let presets = &self.settings.languages;
+        if presets.is_empty() {
+            self.set_status("No preset languages configured.");
+            return;
+        }
+        let next = presets
+            .iter()
+            .position(|l| l.eq_ignore_ascii_case(&task.language))
+            .map_or(0, |i| (i + 1) % presets.len());
+        task.language = presets[next].clone();
+        let message = format!("'{}' is now {}.", task.name, task.language);
+        self.last_language = Some(task.language.clone());
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Gives the selected task the language last set with `l` or on a new
+    /// task, to tag a run of tasks without cycling each one.
+    fn repeat_language(&mut self) {
+        let Some(language) = self.last_language.clone() else {
+            self.set_status("No language to repeat yet. Set one with l first.");
+            return;
+        };
+        let Some(task) = self.todos.get_mut(self.selected_index) else {
+            return;
+        };
+        task.language = language;
+        let message = format!("'{}' is now {}.", task.name, task.language);
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Inserts a fresh copy of the selected task right after it and selects it.
+    fn duplicate_selected(&mut self) {
+        let Some(original) = self.todos.get(self.selected_index) else {
+            return;
+        };
+        let mut copy = Task::new(
+            format!("{} (copy)", original.name),
+            original.language.clone(),
+        );
+        copy.priority = original.priority;
+        copy.estimated_pomodoros = original.estimated_pomodoros;
+        copy.label = original.label;
+        self.selected_index += 1;
+        self.todos.insert(self.selected_index, copy);
+        self.task_inserted(self.selected_index);
+        self.sort_pinned();
+        self.save();
+        self.set_status(format!(
+            "Duplicated as '{}'.",
+            self.todos[self.selected_index].name
+        ));
+    }
+
+    /// Adds the typed name as a new task right after the selected one, with
+    /// its language, priority and label, so the rest of the work can be
+    /// tracked separately.
+    pub fn confirm_split(&mut self) {
+        let name = field_value(&self.split_input);
+        self.split_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        let Some(original) = self.todos.get(self.selected_index) else {
+            return;
+        };
+        if name.is_empty() {
+            return;
+        }
+        let mut part = Task::new(name, original.language.clone());
+        part.priority = original.priority;
+        part.label = original.label;
+        let message = format!("Split '{}' off '{}'.", part.name, original.name);
+        self.selected_index += 1;
+        self.todos.insert(self.selected_index, part);
+        self.task_inserted(self.selected_index);
+        self.sort_pinned();
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Appends a task for every name in the typed file, skipping names the
+    /// list already has.
+    pub fn confirm_import(&mut self) {
+        let path = self.import_input.trim().to_string();
+        self.import_input.clear();
+        self.input_mode = InputMode::NoTyping;
+        self.cursor_position = 0;
+        if path.is_empty() {
+            return;
+        }
+        let tasks = match import_plain(&path) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                self.set_status(format!("Could not read {}: {}", path, err));
+                return;
+            }
+        };
+        let mut added = 0;
+        let mut skipped = 0;
+        for task in tasks {
+            if self
+                .todos
+                .iter()
+                .any(|existing| existing.name.eq_ignore_ascii_case(&task.name))
+            {
+                skipped += 1;
+            } else {
+                self.todos.push(task);
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.save();
+        }
+        let message = match skipped {
+            0 => format!("Imported {} tasks from {}.", added, path),
+            _ => format!(
+                "Imported {} tasks from {} ({} already listed).",
+                added, path, skipped
+            ),
+        };
+        self.set_status(message);
+    }
+
+    /// Moves a task out of the active list into the archive file, keeping
+    /// its counts and history.
+    fn archive_task(&mut self, index: usize) {
+        if index >= self.todos.len() {
+            return;
+        }
+        let mut task = self.todos.remove(index);
+        task.stop_timer();
+
+        let mut archived = load_todos(
+            &self.archive_path,
+            self.settings.long_break_every,
+            self.clock.now(),
+        );
+        let message = format!(
+            "Archived '{}' ({} pomodoros kept).",
+            task.name, task.completed_pomodoros
+        );
+        archived.push(task);
+        if let Err(err) = self.write_tasks(&self.archive_path, &archived, 0) {
+            if let Some(task) = archived.pop() {
+                self.todos.insert(index, task);
+            }
+            self.set_persistent_status(format!("Could not archive: {}", err));
+            return;
+        }
+        self.task_removed(index);
+        self.clamp_selection();
+        self.set_status(message);
+        self.save();
+    }
+
+    /// Moves the highlighted archived task back to the end of the list,
+    /// counts and history included.
+    pub fn restore_archived(&mut self) {
+        if self.archive_index >= self.archived.len() {
+            return;
+        }
+        if self.read_only {
+            self.set_status("Read-only mode: editing is disabled.");
+            return;
+        }
+        if self.is_focus_locked() {
+            self.set_status("Stay focused!");
+            return;
+        }
+        let task = self.archived.remove(self.archive_index);
+        if let Err(err) = self.write_tasks(&self.archive_path, &self.archived, 0) {
+            self.archived.insert(self.archive_index, task);
+            self.set_persistent_status(format!("Could not update the archive: {}", err));
+            return;
+        }
+        self.archive_index = self
+            .archive_index
+            .min(self.archived.len().saturating_sub(1));
+        let message = format!(
+            "Restored '{}' ({} pomodoros).",
+            task.name, task.completed_pomodoros
+        );
+        self.todos.push(task);
+        self.selected_index = self.todos.len() - 1;
+        self.sort_pinned();
+        self.save();
+        self.set_status(message);
+    }
+
+    /// Selects the highest-priority task that has gone the longest without
+    /// attention. Tasks never worked on come first, then fewer completed
+    /// pomodoros.
+    fn suggest_task(&mut self) {
+        let candidates = (0..self.todos.len()).filter(|&i| !self.todos[i].done);
+        let Some(index) = candidates.min_by_key(|&i| {
+            let task = &self.todos[i];
+            (
+                Reverse(task.priority),
+                task.last_worked,
+                task.completed_pomodoros,
+            )
+        }) else {
+            self.set_status(if self.todos.is_empty() {
+                "Nothing to suggest yet."
+            } else {
+                "Every task is done, so there is nothing to suggest."
+            });
+            return;
+        };
+
+        self.selected_index = index;
+        let task = &self.todos[index];
+        let reason = match task.last_worked {
+            Some(time) => format!("last worked {}", format_ago(time)),
+            None => "not worked on yet".to_string(),
+        };
+        self.set_status(format!("Try '{}': {}.", task.name, reason));
+    }
+
+    /// Completed work sessions per local day, oldest first, ending today.
+    pub fn pomodoros_per_day(&self, days: usize) -> Vec<u32> {
+        sessions_per_day(self.todos.iter().flat_map(|task| &task.history), days)
+    }
+
+    pub fn pomodoros_per_hour(&self) -> [u32; 24] {
+        sessions_per_hour(self.todos.iter().flat_map(|task| &task.history))
+    }
+
+    /// Writes today's work as a Markdown table next to the main list.
+    fn export_summary(&mut self) {
+        let today = local_day(SystemTime::now());
+        let path = Path::new(&self.projects[0].path)
+            .with_file_name(format!("{}{}.md", SUMMARY_PREFIX, format_day(today)))
+            .to_string_lossy()
+            .into_owned();
+        let message = match export_markdown(&path, &self.todos, today) {
+            Ok(0) => format!("Nothing worked on today yet; wrote an empty {}.", path),
+            Ok(count) => format!("Exported {} tasks to {}.", count, path),
+            Err(err) => format!("Could not write {}: {}", path, err),
+        };
+        self.set_status(message);
+    }
+
+    /// Focus and completed break time summed over every task.
+    pub fn focus_and_break_time(&self) -> (Duration, Duration) {
+        self.todos
+            .iter()
+            .fold((Duration::ZERO, Duration::ZERO), |(f, b), task| {
+                (f + task.total_focus_time, b + task.total_break_time)
+            })
+    }
+
+    /// Work sessions finished today, across every task.
+    pub fn pomodoros_today(&self) -> usize {
+        let today = local_day(SystemTime::now());
+        self.todos
+            .iter()
+            .flat_map(|task| &task.history)
+            .filter(|session| local_day(session.finished_at) == today)
+            .count()
+    }
+
+    /// Focus time from every task's sessions that finished today.
+    pub fn focus_time_today(&self) -> Duration {
+        let today = local_day(SystemTime::now());
+        self.todos
+            .iter()
+            .flat_map(|task| &task.history)
+            .filter(|session| local_day(session.finished_at) == today)
+            .map(|session| session.duration)
+            .sum()
+    }
+
+    /// Focus time across every task ever tracked, archived ones included.
+    pub fn lifetime_focus_time(&self) -> Duration {
+        self.todos
+            .iter()
+            .chain(&self.archived)
+            .map(|task| task.total_focus_time)
+            .sum()
+    }
+
+    pub fn language_stats(&self) -> HashMap<String, LanguageStats> {
+        let mut stats: HashMap<String, LanguageStats> = HashMap::new();
+        for task in &self.todos {
+            let entry = stats.entry(task.language.clone()).or_default();
+            entry.tasks += 1;
+            entry.pomodoros += task.completed_pomodoros;
+            entry.focus_time += task.total_focus_time;
+        }
+        stats
+    }
+
+    /// Shows a message for the configured duration. A persistent message
+    /// already on screen is not replaced until it is dismissed.
+    pub fn set_status(&mut self, text: impl Into<String>) {
+        if self.status_message.as_ref().is_some_and(|m| m.persistent) {
+            return;
+        }
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            shown_at: self.clock.now(),
+            persistent: false,
+        });
+    }
+
+    /// Shows a message that stays until dismissed with 'c'.
+    fn set_persistent_status(&mut self, text: impl Into<String>) {
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            shown_at: self.clock.now(),
+            persistent: true,
+        });
+    }
+
+    pub fn status_message(&mut self) -> Option<String> {
+        if let Some(message) = &self.status_message {
+            if message.persistent
+                || self.clock.since(message.shown_at) < self.settings.message_duration
+            {
+                return Some(message.text.clone());
+            }
+        }
+        self.status_message = None;
+        None
+    }
+
+    pub fn save(&mut self) {
+        // Hold off until it's settled whose version of the file wins.
+        if matches!(self.pending_confirm, Some(Confirmation::Reload)) {
+            self.request_save();
+            return;
+        }
+        self.save_due = None;
+        self.error_banner = self
+            .write_tasks(&self.data_path, &self.todos, self.settings.backup_count)
+            .err()
+            .map(|err| {
+                format!(
+                    "Can't write {}: {}. Changes are not being saved.",
+                    self.data_path, err
+                )
+            });
+        self.data_mtime = modified_at(&self.data_path);
+    }
+
+    /// `save_todos`, except that a dry run pretends it succeeded.
+    fn write_tasks(&self, path: &str, tasks: &[Task], backups: u32) -> io::Result<()> {
+        if self.no_save {
+            return Ok(());
+        }
+        save_todos(path, tasks, backups, self.clock.now())
+    }
+
+    /// Picks up edits another program (or a sync client) made to the data
+    /// file. Reloads straight away unless a save is pending here, in which
+    /// case it asks first.
+    pub fn check_external_change(&mut self) {
+        let modified = modified_at(&self.data_path);
+        if modified.is_none() || modified == self.data_mtime || self.pending_confirm.is_some() {
+            return;
+        }
+        if self.save_due.is_some() {
+            self.pending_confirm = Some(Confirmation::Reload);
+        } else {
+            self.reload();
+        }
+    }
+
+    pub fn reload(&mut self) {
+        self.todos = load_todos(
+            &self.data_path,
+            self.settings.long_break_every,
+            self.clock.now(),
+        );
+        self.data_mtime = modified_at(&self.data_path);
+        self.save_due = None;
+        self.clamp_selection();
+        self.active_task = self.active_task.filter(|&i| i < self.todos.len());
+        self.focus_task = self.focus_task.filter(|&i| i < self.todos.len());
+        self.blur_paused.clear();
+        self.check_readable();
+        self.reset_recurring();
+        self.set_status(format!(
+            "Reloaded {} after it changed on disk.",
+            self.data_path
+        ));
+    }
+
+    /// Keeps the list in memory over the one on disk by writing it out.
+    pub fn keep_local_changes(&mut self) {
+        self.save();
+        self.set_status("Kept the changes made here; the file on disk was overwritten.");
+    }
+
+    /// `load_todos` treats any failure as an empty list, which is right for
+    /// a file that doesn't exist yet but hides one that can't be read.
+    fn check_readable(&mut self) {
+        self.error_banner = match fs::File::open(&self.data_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                Some(format!("Can't read {}: {}", self.data_path, err))
+            }
+            _ => None,
+        };
+    }
+
+    /// Schedules a save shortly from now so bursts of timer events are
+    /// written once.
+    fn request_save(&mut self) {
+        self.save_due
+            .get_or_insert_with(|| self.clock.now() + SAVE_DEBOUNCE);
+    }
+
+    pub fn flush_pending_save(&mut self) {
+        if self.save_due.is_some_and(|due| self.clock.now() >= due) {
+            self.save();
+        }
+    }
+
+    pub fn handle_input(&mut self, c: char) {
+        match self.input_mode {
+            InputMode::Form => {
+                self.form.focused_value().push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Minutes => {
+                if c.is_ascii_digit() {
+                    self.minutes_input.push(c);
+                    self.cursor_position += 1;
+                }
+            }
+            InputMode::Checklist => {
+                self.checklist_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Project => {
+                self.project_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Distraction => {
+                self.distraction_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Split => {
+                self.split_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Intent => {
+                self.intent_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Import => {
+                self.import_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::Command => {
+                self.command_input.push(c);
+                self.cursor_position += 1;
+            }
+            InputMode::NoTyping => {}
+        }
+    }
+
+    /// Inserts pasted text as if typed. Line breaks become spaces since
+    /// every field is a single line.
+    pub fn handle_paste(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' | '\r' | '\t' => self.handle_input(' '),
+                c if c.is_control() => {}
+                c => self.handle_input(c),
+            }
+        }
+    }
+
+    pub fn handle_backspace(&mut self) {
+        match self.input_mode {
+            InputMode::Form => {
+                if self.form.focused_value().pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Minutes => {
+                if !self.minutes_input.is_empty() {
+                    self.minutes_input.pop();
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Checklist => {
+                if !self.checklist_input.is_empty() {
+                    self.checklist_input.pop();
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Project => {
+                if self.project_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Distraction => {
+                if self.distraction_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Split => {
+                if self.split_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Intent => {
+                if self.intent_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Import => {
+                if self.import_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::Command => {
+                if self.command_input.pop().is_some() {
+                    self.cursor_position = self.cursor_position.saturating_sub(1);
+                }
+            }
+            InputMode::NoTyping => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::AlertSound;
+    use crate::task::{BREAK_DURATION, LONG_BREAK_DURATION, LONG_BREAK_EVERY};
+    use std::time::UNIX_EPOCH;
+
+    /// An app backed by a fresh directory and a manual clock, with sound and
+    /// the session log off.
+    fn test_app(name: &str) -> App {
+        let dir = env::temp_dir().join(format!("todo-tui-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut app = App::new(
+            dir.join(TODO_FILE).to_string_lossy().into_owned(),
+            Mode::default(),
+            Clock::manual(),
+        );
+        app.settings = Settings {
+            alert_sound: AlertSound::Off,
+            log_sessions: false,
+            ..Settings::default()
+        };
+        app.todos
+            .push(Task::new("Write tests".to_string(), "Rust".to_string()));
+        app
+    }
+
+    fn cleanup(app: &App) {
+        if let Some(dir) = Path::new(&app.data_path).parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn work_turns_into_break_then_idle() {
+        let mut app = test_app("cycle");
+        app.start_pomodoro(None);
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Work));
+
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        let task = &app.todos[0];
+        assert!(matches!(task.pomodoro_state, PomodoroState::Break));
+        assert_eq!(task.completed_pomodoros, 1);
+        assert_eq!(task.history.len(), 1);
+        assert_eq!(task.total_focus_time, WORK_DURATION);
+
+        app.clock.advance(BREAK_DURATION);
+        app.update_pomodoro();
+        let task = &app.todos[0];
+        assert!(matches!(task.pomodoro_state, PomodoroState::Idle));
+        assert!(task.pomodoro_start.is_none());
+        assert_eq!(task.total_break_time, BREAK_DURATION);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn work_ends_exactly_at_its_length() {
+        let mut app = test_app("boundary");
+        app.start_pomodoro(None);
+        app.clock.advance(WORK_DURATION - Duration::from_nanos(1));
+        app.update_pomodoro();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Work));
+        assert_eq!(app.todos[0].completed_pomodoros, 0);
+
+        app.clock.advance(Duration::from_nanos(1));
+        app.update_pomodoro();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Break));
+        assert_eq!(app.todos[0].completed_pomodoros, 1);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn paused_time_does_not_count() {
+        let mut app = test_app("pause");
+        app.start_pomodoro(None);
+        app.clock.advance(Duration::from_secs(60));
+        app.toggle_pomodoro();
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert_eq!(
+            app.todos[0].elapsed(app.clock.now()),
+            Some(Duration::from_secs(60))
+        );
+
+        app.toggle_pomodoro();
+        app.clock.advance(WORK_DURATION - Duration::from_secs(60));
+        app.update_pomodoro();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Break));
+        cleanup(&app);
+    }
+
+    #[test]
+    fn fourth_pomodoro_earns_a_long_break() {
+        let mut app = test_app("long-break");
+        app.todos[0].completed_pomodoros = 3;
+        app.start_pomodoro(None);
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Break));
+        assert_eq!(
+            app.todos[0].break_duration(LONG_BREAK_EVERY),
+            LONG_BREAK_DURATION
+        );
+        cleanup(&app);
+    }
+
+    #[test]
+    fn reaching_the_estimate_marks_the_task_done() {
+        let mut app = test_app("auto-complete");
+        app.todos[0].estimated_pomodoros = Some(2);
+        app.todos[0].completed_pomodoros = 1;
+        app.start_pomodoro(None);
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert!(!app.todos[0].done);
+
+        app.settings.auto_complete = true;
+        app.todos[0].completed_pomodoros = 1;
+        app.todos[0].pomodoro_state = PomodoroState::Work;
+        app.todos[0].pomodoro_start = Some(app.clock.now());
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert!(app.todos[0].done);
+        assert_eq!(app.todos[0].completed_pomodoros, 2);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn pause_all_stops_timers_away_from_the_selection() {
+        let mut app = test_app("pause-all");
+        app.todos
+            .push(Task::new("Review PR".to_string(), "Go".to_string()));
+        app.todos
+            .push(Task::new("Inbox".to_string(), "Other".to_string()));
+        app.start_pomodoro(None);
+        app.selected_index = 1;
+        app.toggle_stopwatch();
+        app.selected_index = 2;
+
+        app.perform(Action::PauseAll);
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert!(app.todos[0].paused.is_some());
+        assert!(app.todos[1].paused.is_some());
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Work));
+        assert!(app.todos[2].pomodoro_start.is_none());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn dry_run_leaves_the_file_alone() {
+        let mut app = test_app("no-save");
+        app.save();
+        let before = fs::read_to_string(&app.data_path).unwrap();
+
+        app.no_save = true;
+        app.todos
+            .push(Task::new("Try it out".to_string(), "Go".to_string()));
+        app.save();
+        assert_eq!(fs::read_to_string(&app.data_path).unwrap(), before);
+        assert!(app.error_banner.is_none());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn checklist_items_with_separators_survive_a_reload() {
+        let mut app = test_app("checklist");
+        app.checklist_input = "Read docs | take notes; ask".to_string();
+        app.confirm_checklist_item();
+
+        let loaded = load_todos(&app.data_path, LONG_BREAK_EVERY, app.clock.now());
+        assert_eq!(
+            loaded[0].checklist,
+            vec![("Read docs / take notes, ask".to_string(), false)]
+        );
+        assert_eq!(loaded[0].language, "Rust");
+        cleanup(&app);
+    }
+
+    #[test]
+    fn dry_run_start_leaves_rolled_over_recurring_tasks_on_disk() {
+        let dir = env::temp_dir().join(format!("todo-tui-no-save-start-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TODO_FILE).to_string_lossy().into_owned();
+        let mut task = Task::new("Stretch".to_string(), "Other".to_string());
+        task.recurring = true;
+        task.completed_pomodoros = 2;
+        task.reset_at = Some(UNIX_EPOCH + Duration::from_secs(24 * 60 * 60));
+        save_todos(&path, &[task], 0, Instant::now()).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        let app = App::new(
+            path.clone(),
+            Mode {
+                no_save: true,
+                ..Mode::default()
+            },
+            Clock::manual(),
+        );
+        assert_eq!(app.todos[0].completed_pomodoros, 0);
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dry_run_projects_stay_in_memory() {
+        let mut app = test_app("no-save-project");
+        app.no_save = true;
+        app.project_input = "Side".to_string();
+        app.confirm_new_project();
+        assert_eq!(app.projects.last().unwrap().name, "Side");
+        let dir = Path::new(&app.data_path).with_file_name(PROJECTS_DIR);
+        assert!(!dir.exists());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn p_follows_the_running_timer_not_the_selection() {
+        let mut app = test_app("focus-task");
+        app.todos
+            .push(Task::new("Review PR".to_string(), "Go".to_string()));
+        app.start_pomodoro(None);
+        app.selected_index = 1;
+        assert_eq!(app.timer_index(), 0);
+
+        app.perform(Action::TogglePomodoro);
+        assert!(app.todos[0].paused.is_some());
+        assert!(app.todos[1].pomodoro_start.is_none());
+
+        app.perform(Action::StartSelected);
+        assert!(matches!(app.todos[1].pomodoro_state, PomodoroState::Work));
+        assert_eq!(app.timer_index(), 1);
+        assert!(app.todos[0].paused.is_some());
+
+        app.selected_index = 0;
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert_eq!(app.todos[1].completed_pomodoros, 1);
+        assert_eq!(app.timer_index(), 1);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn suggestions_skip_done_tasks() {
+        let mut app = test_app("suggest");
+        app.todos[0].done = true;
+        app.todos
+            .push(Task::new("Review PR".to_string(), "Go".to_string()));
+        app.todos[1].last_worked = Some(SystemTime::now());
+        app.perform(Action::Suggest);
+        assert_eq!(app.selected_index, 1);
+
+        app.todos[1].done = true;
+        app.selected_index = 0;
+        app.perform(Action::Suggest);
+        assert_eq!(app.selected_index, 0);
+        assert!(app
+            .status_message
+            .as_ref()
+            .is_some_and(|status| status.text.contains("nothing to suggest")));
+        cleanup(&app);
+    }
+
+    #[test]
+    fn pipes_in_new_task_names_survive_a_reload() {
+        let mut app = test_app("pipes");
+        app.todos.clear();
+        app.form.values[FIELD_NAME] = "Fix | build".to_string();
+        app.form.values[FIELD_LANGUAGE] = "Rust".to_string();
+        app.confirm_new_task();
+        app.split_input = "Docs | tests".to_string();
+        app.confirm_split();
+
+        let loaded = load_todos(&app.data_path, LONG_BREAK_EVERY, app.clock.now());
+        let names: Vec<(&str, &str)> = loaded
+            .iter()
+            .map(|task| (task.name.as_str(), task.language.as_str()))
+            .collect();
+        assert_eq!(names, [("Fix / build", "Rust"), ("Docs / tests", "Rust")]);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn running_timers_survive_a_reload_on_the_app_clock() {
+        let mut app = test_app("reload-timer");
+        app.start_pomodoro(None);
+        app.clock.advance(WORK_DURATION - Duration::from_secs(60));
+        app.save();
+        app.reload();
+
+        let task = &app.todos[0];
+        assert!(matches!(task.pomodoro_state, PomodoroState::Work));
+        // The saved start time has whole-second precision.
+        let elapsed = task.elapsed(app.clock.now()).unwrap();
+        assert!(elapsed >= WORK_DURATION - Duration::from_secs(60));
+        assert!(elapsed <= WORK_DURATION - Duration::from_secs(58));
+
+        app.clock.advance(Duration::from_secs(60));
+        app.update_pomodoro();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Break));
+        assert_eq!(app.todos[0].completed_pomodoros, 1);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn starting_a_done_task_asks_first() {
+        let mut app = test_app("done");
+        app.todos[0].done = true;
+        app.start_pomodoro(None);
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Idle));
+        assert!(matches!(
+            app.pending_confirm,
+            Some(Confirmation::StartDone(None))
+        ));
+        cleanup(&app);
+    }
+
+    #[test]
+    fn saved_tasks_load_back() {
+        let mut app = test_app("persist");
+        app.todos[0].pinned = true;
+        app.todos[0].completed_pomodoros = 2;
+        app.start_pomodoro(None);
+
+        let loaded = load_todos(&app.data_path, LONG_BREAK_EVERY, app.clock.now());
+        assert_eq!(loaded.len(), 1);
+        let task = &loaded[0];
+        assert_eq!(task.name, "Write tests");
+        assert_eq!(task.language, "Rust");
+        assert_eq!(task.completed_pomodoros, 2);
+        assert!(task.pinned);
+        assert!(matches!(task.pomodoro_state, PomodoroState::Work));
+        cleanup(&app);
+    }
+
+    #[test]
+    fn grouped_navigation_skips_headers() {
+        let mut app = test_app("groups");
+        app.todos
+            .push(Task::new("Ship it".to_string(), "Rust".to_string()));
+        app.todos
+            .push(Task::new("Review".to_string(), "Rust".to_string()));
+        app.todos[0].done = true;
+        app.selected_index = 2;
+        app.start_pomodoro(None);
+        app.group_by_state = true;
+
+        let rows: Vec<String> = app
+            .list_rows()
+            .iter()
+            .map(|row| match row {
+                ListRow::Header(header) => header.to_string(),
+                ListRow::Task(i) => app.todos[*i].name.clone(),
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "Running",
+                "Review",
+                "Idle",
+                "Ship it",
+                "Completed",
+                "Write tests"
+            ]
+        );
+
+        app.move_selection(true);
+        assert_eq!(app.selected_index, 1);
+        app.move_selection(true);
+        assert_eq!(app.selected_index, 0);
+        app.move_selection(true);
+        assert_eq!(app.selected_index, 0);
+        app.move_selection(false);
+        app.move_selection(false);
+        assert_eq!(app.selected_index, 2);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn failed_save_raises_the_banner_until_one_succeeds() {
+        let mut app = test_app("banner");
+        let data_path = app.data_path.clone();
+        app.data_path = Path::new(&data_path)
+            .with_file_name("missing")
+            .join(TODO_FILE)
+            .to_string_lossy()
+            .into_owned();
+        app.save();
+        assert!(app.error_banner.is_some());
+
+        app.data_path = data_path;
+        app.save();
+        assert!(app.error_banner.is_none());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn session_intent_is_asked_for_and_cleared_at_the_end() {
+        let mut app = test_app("intent");
+        app.settings.ask_intent = true;
+        app.start_pomodoro(None);
+        assert!(matches!(app.input_mode, InputMode::Intent));
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Idle));
+
+        app.handle_paste("Finish the parser");
+        app.confirm_intent();
+        assert!(matches!(app.todos[0].pomodoro_state, PomodoroState::Work));
+        assert_eq!(app.todos[0].intent.as_deref(), Some("Finish the parser"));
+
+        app.clock.advance(WORK_DURATION);
+        app.update_pomodoro();
+        assert_eq!(app.todos[0].intent, None);
+        cleanup(&app);
+    }
+
+    #[test]
+    fn outside_edits_reload_or_ask_first() {
+        let mut app = test_app("external");
+        app.save();
+        fs::write(&app.data_path, "Written elsewhere | Go | 3\n").unwrap();
+        app.check_external_change();
+        assert_eq!(app.todos.len(), 1);
+        assert_eq!(app.todos[0].name, "Written elsewhere");
+
+        app.request_save();
+        fs::write(&app.data_path, "Again | Go | 0\n").unwrap();
+        app.check_external_change();
+        assert!(matches!(app.pending_confirm, Some(Confirmation::Reload)));
+        app.flush_pending_save();
+        assert_eq!(
+            load_todos(&app.data_path, LONG_BREAK_EVERY, app.clock.now())[0].name,
+            "Again"
+        );
+        cleanup(&app);
+    }
+
+    #[test]
+    fn archived_tasks_can_be_restored() {
+        let mut app = test_app("archive");
+        app.todos[0].completed_pomodoros = 3;
+        app.archive_task(0);
+        assert!(app.todos.is_empty());
+
+        app.perform(Action::Archived);
+        assert!(matches!(app.view, View::Archive));
+        assert_eq!(app.archived.len(), 1);
+        app.restore_archived();
+        assert!(app.archived.is_empty());
+        assert_eq!(app.todos[0].name, "Write tests");
+        assert_eq!(app.todos[0].completed_pomodoros, 3);
+        assert!(load_todos(&app.archive_path, LONG_BREAK_EVERY, app.clock.now()).is_empty());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn command_palette_dispatches_names_and_aliases() {
+        let mut app = test_app("palette");
+        app.perform(Action::Command);
+        app.handle_paste("done");
+        assert!(!app.confirm_command());
+        assert!(app.todos[0].done);
+
+        app.perform(Action::Command);
+        app.handle_paste("stats");
+        app.confirm_command();
+        assert!(matches!(app.view, View::Stats));
+
+        app.perform(Action::Command);
+        app.handle_paste("delete");
+        app.confirm_command();
+        assert!(app.todos.is_empty());
+
+        app.perform(Action::Command);
+        app.handle_paste("quit");
+        assert!(app.confirm_command());
+        cleanup(&app);
+    }
+
+    #[test]
+    fn controls_line_keeps_to_core_keys_and_help_lists_the_rest() {
+        let mut app = test_app("help");
+        assert!(app.controls_hint.contains("?=all keys"));
+        assert!(!app.controls_hint.contains("stopwatch"));
+        assert!(app.key_help.iter().any(|entry| entry == "t  stopwatch"));
+        assert!(app.key_help.iter().any(|entry| entry == "q  quit"));
+
+        app.perform(Action::Help);
+        assert!(matches!(app.view, View::Help));
+        app.perform(Action::Help);
+        assert!(matches!(app.view, View::Tasks));
+        cleanup(&app);
+    }
+}
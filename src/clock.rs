@@ -0,0 +1,42 @@
+//! The time source for timers, so tests can move time by hand.
+
+#[cfg(test)]
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Where timers read the current instant from. The app runs on the system
+/// clock; tests use a manual one that only moves when advanced. Saved timers
+/// are still converted to wall-clock time with the system clock.
+pub enum Clock {
+    System,
+    #[cfg(test)]
+    Manual(Cell<Instant>),
+}
+
+impl Clock {
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::System => Instant::now(),
+            #[cfg(test)]
+            Clock::Manual(now) => now.get(),
+        }
+    }
+
+    /// Time since `earlier`, or zero if it lies ahead.
+    pub fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+#[cfg(test)]
+impl Clock {
+    pub fn manual() -> Self {
+        Clock::Manual(Cell::new(Instant::now()))
+    }
+
+    pub fn advance(&self, by: Duration) {
+        if let Clock::Manual(now) = self {
+            now.set(now.get() + by);
+        }
+    }
+}
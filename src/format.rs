@@ -0,0 +1,179 @@
+//! Human-readable durations and local calendar dates.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+/// Placeholders a `list_format` template may use.
+pub const LIST_PLACEHOLDERS: [&str; 8] = [
+    "name",
+    "language",
+    "state",
+    "completed",
+    "estimate",
+    "priority",
+    "label",
+    "pin",
+];
+
+/// Replaces every `{key}` in `template` with `value(key)`. Keys it has no
+/// value for, and braces that don't close, are kept as written.
+pub fn fill_template(template: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) => {
+                let key = &after[..close];
+                match value(key) {
+                    Some(text) => out.push_str(&text),
+                    None => out.push_str(&rest[open..open + close + 2]),
+                }
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push_str(&rest[open..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The `{key}`s in `template` that aren't in `known`.
+pub fn unknown_placeholders(template: &str, known: &[&str]) -> Vec<String> {
+    let mut unknown = Vec::new();
+    fill_template(template, |key| {
+        if !known.contains(&key) {
+            unknown.push(format!("{{{}}}", key));
+        }
+        Some(String::new())
+    });
+    unknown
+}
+
+pub fn format_hours_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Coarse relative time such as "3 days ago".
+pub fn format_ago(time: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or_default()
+        .as_secs();
+    let (value, unit) = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (secs / 60, "minute"),
+        3_600..=86_399 => (secs / 3_600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{} {}{} ago", value, unit, plural)
+}
+
+/// Trims the ends and squeezes inner runs of whitespace to a single space.
+pub fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Typed text ready to be stored as one field of a task line: whitespace
+/// collapsed and `|`, which separates the fields, swapped for `/`.
+pub fn field_value(value: &str) -> String {
+    collapse_whitespace(&value.replace('|', "/"))
+}
+
+/// "YYYY-MM-DD HH:MM" in the local timezone.
+pub fn format_local_time(time: SystemTime) -> String {
+    let secs = unix_secs(time) as i64 + utc_offset_secs();
+    let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+    format!(
+        "{} {:02}:{:02}",
+        format_day(secs.div_euclid(SECS_PER_DAY)),
+        secs_of_day / 3_600,
+        secs_of_day / 60 % 60
+    )
+}
+
+/// "YYYY-MM-DD" for a day count as returned by `local_day`.
+pub fn format_day(days: i64) -> String {
+    // Civil date from a day count (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Days since the epoch in the local timezone, so "today" matches the wall clock.
+pub fn local_day(time: SystemTime) -> i64 {
+    (unix_secs(time) as i64 + utc_offset_secs()).div_euclid(SECS_PER_DAY)
+}
+
+/// Hour of the local day (0-23) that `time` falls in.
+pub fn local_hour(time: SystemTime) -> usize {
+    ((unix_secs(time) as i64 + utc_offset_secs()).rem_euclid(SECS_PER_DAY) / 3600) as usize
+}
+
+pub fn weekday_name(day: i64) -> &'static str {
+    WEEKDAYS[day.rem_euclid(7) as usize]
+}
+
+#[cfg(unix)]
+fn utc_offset_secs() -> i64 {
+    // SAFETY: `localtime_r` only writes into the `tm` we hand it.
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            0
+        } else {
+            tm.tm_gmtoff as i64
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn utc_offset_secs() -> i64 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_fills_known_keys_and_keeps_the_rest() {
+        let row = fill_template("{name} [{language}] {owner} {", |key| match key {
+            "name" => Some("Docs".to_string()),
+            "language" => Some("Rust".to_string()),
+            _ => None,
+        });
+        assert_eq!(row, "Docs [Rust] {owner} {");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_reported() {
+        assert_eq!(
+            unknown_placeholders("{name} {owner} {state} {due}", &LIST_PLACEHOLDERS),
+            vec!["{owner}", "{due}"]
+        );
+        assert!(unknown_placeholders("{completed}/{estimate}", &LIST_PLACEHOLDERS).is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+//! Single-key commands and their (re)bindings.
+
+use std::collections::HashMap;
+
+/// A command bound to a single key while nothing is being typed. Any of
+/// them can be rebound in settings.txt with a `key_<name> = <char>` line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    AddTask,
+    TogglePomodoro,
+    StartSelected,
+    PauseAll,
+    MarkActive,
+    StopTimer,
+    ExtendSession,
+    SkipBreak,
+    Stopwatch,
+    QuickTimer,
+    CustomMinutes,
+    AddChecklistItem,
+    IncreaseCount,
+    DecreaseCount,
+    Duplicate,
+    Split,
+    Import,
+    Pin,
+    ToggleDone,
+    Reopen,
+    Recurring,
+    Sort,
+    Label,
+    Language,
+    RepeatLanguage,
+    Archive,
+    Suggest,
+    FocusLock,
+    Stats,
+    Export,
+    OpenFolder,
+    CopyName,
+    Zen,
+    CompactList,
+    GroupList,
+    Theme,
+    HideSeconds,
+    TaskHistory,
+    NewProject,
+    OpenSettings,
+    Interruption,
+    Jot,
+    Distractions,
+    Archived,
+    ClearMessage,
+    Command,
+    Help,
+    ResetStats,
+    Quit,
+}
+
+impl Action {
+    const ALL: [Action; 49] = [
+        Action::AddTask,
+        Action::TogglePomodoro,
+        Action::StartSelected,
+        Action::PauseAll,
+        Action::MarkActive,
+        Action::StopTimer,
+        Action::ExtendSession,
+        Action::SkipBreak,
+        Action::Stopwatch,
+        Action::QuickTimer,
+        Action::CustomMinutes,
+        Action::AddChecklistItem,
+        Action::IncreaseCount,
+        Action::DecreaseCount,
+        Action::Duplicate,
+        Action::Split,
+        Action::Import,
+        Action::Pin,
+        Action::ToggleDone,
+        Action::Reopen,
+        Action::Recurring,
+        Action::Sort,
+        Action::Label,
+        Action::Language,
+        Action::RepeatLanguage,
+        Action::Archive,
+        Action::Suggest,
+        Action::FocusLock,
+        Action::Stats,
+        Action::Export,
+        Action::OpenFolder,
+        Action::CopyName,
+        Action::Zen,
+        Action::CompactList,
+        Action::GroupList,
+        Action::Theme,
+        Action::HideSeconds,
+        Action::TaskHistory,
+        Action::NewProject,
+        Action::OpenSettings,
+        Action::Interruption,
+        Action::Jot,
+        Action::Distractions,
+        Action::Archived,
+        Action::ClearMessage,
+        Action::Command,
+        Action::Help,
+        Action::ResetStats,
+        Action::Quit,
+    ];
+
+    /// The settings.txt name, the default key and the controls hint.
+    fn spec(self) -> (&'static str, char, &'static str) {
+        match self {
+            Action::AddTask => ("add_task", 'i', "add task"),
+            Action::TogglePomodoro => ("start_pause", 'p', "start/pause"),
+            Action::StartSelected => ("start_selected", 'w', "start selected"),
+            Action::PauseAll => ("pause_all", 'x', "pause all"),
+            Action::MarkActive => ("mark_active", 'M', "mark active"),
+            Action::StopTimer => ("stop_timer", 'r', "stop timer"),
+            Action::ExtendSession => ("extend_session", '>', "+5 min"),
+            Action::SkipBreak => ("skip_break", 'k', "skip break"),
+            Action::Stopwatch => ("stopwatch", 't', "stopwatch"),
+            Action::QuickTimer => ("quick_timer", 'T', "quick timer"),
+            Action::CustomMinutes => ("custom_minutes", 'm', "custom minutes"),
+            Action::AddChecklistItem => ("add_checklist_item", 'u', "add checklist item"),
+            Action::IncreaseCount => ("increase_count", '+', "count +1"),
+            Action::DecreaseCount => ("decrease_count", '-', "count -1"),
+            Action::Duplicate => ("duplicate", 'd', "duplicate"),
+            Action::Split => ("split", 'S', "split"),
+            Action::Import => ("import", 'I', "import list"),
+            Action::Pin => ("pin", 'P', "pin"),
+            Action::ToggleDone => ("toggle_done", 'D', "done"),
+            Action::Reopen => ("reopen", 'C', "reopen"),
+            Action::Recurring => ("recurring", 'R', "recurring"),
+            Action::Sort => ("sort", 'A', "sort"),
+            Action::Label => ("label", 'e', "label"),
+            Action::Language => ("language", 'l', "language"),
+            Action::RepeatLanguage => ("repeat_language", 'L', "repeat language"),
+            Action::Archive => ("archive", 'a', "archive"),
+            Action::Suggest => ("suggest", 'n', "suggest"),
+            Action::FocusLock => ("focus_lock", 'f', "focus lock"),
+            Action::Stats => ("stats", 's', "stats"),
+            Action::Export => ("export_summary", 'E', "export summary"),
+            Action::OpenFolder => ("open_folder", 'O', "open data folder"),
+            Action::CopyName => ("copy_name", 'y', "copy name"),
+            Action::Zen => ("zen", 'z', "zen"),
+            Action::CompactList => ("compact_list", 'v', "compact list"),
+            Action::GroupList => ("group_list", 'g', "group by state"),
+            Action::Theme => ("theme", 'b', "light/dark"),
+            Action::HideSeconds => ("hide_seconds", 'h', "hide seconds"),
+            Action::TaskHistory => ("task_history", 'H', "task history"),
+            Action::NewProject => ("new_project", 'N', "new project"),
+            Action::OpenSettings => ("settings", 'o', "settings"),
+            Action::Interruption => ("interruption", '!', "interruption"),
+            Action::Jot => ("jot_distraction", 'j', "jot distraction"),
+            Action::Distractions => ("distractions", 'J', "review distractions"),
+            Action::Archived => ("archived", 'W', "archived tasks"),
+            Action::ClearMessage => ("clear_message", 'c', "clear message"),
+            Action::Command => ("command", ':', "command"),
+            Action::Help => ("help", '?', "all keys"),
+            Action::ResetStats => ("reset_stats", 'X', "reset stats"),
+            Action::Quit => ("quit", 'q', "quit"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.spec().0
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// A word typed into the command palette: an action name or one of the
+    /// shorter aliases.
+    pub fn from_command(word: &str) -> Option<Action> {
+        Action::from_name(word).or_else(|| {
+            COMMAND_ALIASES
+                .into_iter()
+                .find(|(alias, _)| *alias == word)
+                .map(|(_, action)| action)
+        })
+    }
+
+    /// Changes the task list, so it is refused in read-only mode.
+    pub fn edits(self) -> bool {
+        matches!(
+            self,
+            Action::AddTask
+                | Action::Duplicate
+                | Action::Split
+                | Action::Import
+                | Action::Archive
+                | Action::AddChecklistItem
+                | Action::Language
+                | Action::RepeatLanguage
+                | Action::Label
+                | Action::Pin
+                | Action::ToggleDone
+                | Action::Reopen
+                | Action::Recurring
+                | Action::NewProject
+                | Action::Sort
+                | Action::ResetStats
+                | Action::IncreaseCount
+                | Action::DecreaseCount
+        )
+    }
+
+    /// Reshuffles the list, so it is refused while focus lock holds.
+    pub fn breaks_focus(self) -> bool {
+        matches!(
+            self,
+            Action::AddTask
+                | Action::Duplicate
+                | Action::Split
+                | Action::Import
+                | Action::Archive
+                | Action::Suggest
+                | Action::Pin
+                | Action::NewProject
+                | Action::Sort
+                | Action::ResetStats
+        )
+    }
+}
+
+/// The bindings shown in the controls line; the rest live in the help view.
+const CORE_ACTIONS: [Action; 7] = [
+    Action::AddTask,
+    Action::TogglePomodoro,
+    Action::StopTimer,
+    Action::ToggleDone,
+    Action::OpenSettings,
+    Action::Help,
+    Action::Quit,
+];
+
+/// Short words the command palette accepts on top of the action names.
+const COMMAND_ALIASES: [(&str, Action); 6] = [
+    ("add", Action::AddTask),
+    ("start", Action::TogglePomodoro),
+    ("pause", Action::TogglePomodoro),
+    ("done", Action::ToggleDone),
+    ("export", Action::Export),
+    ("history", Action::TaskHistory),
+];
+
+/// Resolves the default keys and the rebindings from settings into one map.
+/// Rebindings win over defaults, and the first of two rebindings to the same
+/// key wins; every key that ends up claimed twice is reported.
+pub fn build_keymap(overrides: &[(Action, char)]) -> (HashMap<char, Action>, Vec<String>) {
+    let mut keymap: HashMap<char, Action> = HashMap::new();
+    let mut conflicts = Vec::new();
+    let defaults = Action::ALL
+        .into_iter()
+        .filter(|action| !overrides.iter().any(|(rebound, _)| rebound == action))
+        .map(|action| (action, action.spec().1));
+    for (action, key) in overrides.iter().copied().chain(defaults) {
+        match keymap.get(&key) {
+            Some(owner) => conflicts.push(format!(
+                "Key '{}' is bound to both {} and {}; {} is unbound.",
+                key,
+                owner.name(),
+                action.name(),
+                action.name()
+            )),
+            None => {
+                keymap.insert(key, action);
+            }
+        }
+    }
+    (keymap, conflicts)
+}
+
+/// The controls line for the current bindings.
+pub fn controls_hint(keymap: &HashMap<char, Action>) -> String {
+    let mut hint = String::from("  ↑/↓=navigate");
+    for action in CORE_ACTIONS {
+        if let Some(key) = key_for(keymap, action) {
+            hint.push_str(&format!("  {}={}", key, action.spec().2));
+        }
+    }
+    hint
+}
+
+/// Every binding, one entry each, for the help view.
+pub fn key_help(keymap: &HashMap<char, Action>) -> Vec<String> {
+    let mut entries = vec![
+        "↑/↓  navigate".to_string(),
+        "tab  next project".to_string(),
+        "del  remove".to_string(),
+    ];
+    for action in Action::ALL {
+        if let Some(key) = key_for(keymap, action) {
+            entries.push(format!("{}  {}", key, action.spec().2));
+        }
+    }
+    entries
+}
+
+fn key_for(keymap: &HashMap<char, Action>, action: Action) -> Option<char> {
+    keymap
+        .iter()
+        .find(|(_, bound)| **bound == action)
+        .map(|(&key, _)| key)
+}
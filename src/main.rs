@@ -1,324 +1,650 @@
+mod app;
+mod clock;
+mod format;
+mod keymap;
+mod settings;
+mod sound;
+mod storage;
+mod task;
+
+use crate::{
+    app::{
+        App, Confirmation, InputMode, ListRow, Mode, TaskForm, View, FIELD_ESTIMATE,
+        FIELD_PRIORITY, FORM_FIELDS,
+    },
+    clock::Clock,
+    format::{fill_template, format_ago, format_hours_minutes, local_day, weekday_name},
+    settings::{Text, Theme},
+    task::{sessions_per_day, LanguageStats, PomodoroState, WORK_DURATION},
+};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{
+        self, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
-    Terminal,
+    widgets::{
+        Block, Borders, Cell, Gauge, List, ListItem, Paragraph, Row, Sparkline, Table, Wrap,
+    },
+    Frame, Terminal,
 };
 use std::{
+    env,
     fs::{self, OpenOptions},
     io::{self, Write},
-    time::{Duration, Instant},
+    path::PathBuf,
+    process,
+    time::{Duration, SystemTime},
 };
 
-enum InputMode {
-    Task,
-    Language,
-    NoTyping,
-}
-
-#[derive(Debug)]
-enum PomodoroState {
-    Idle,
-    Work,
-    Break,
-}
+const USAGE: &str = "Usage: todo-tui [--file <path>] [--read-only] [--no-save] [--debug]
 
-const WORK_DURATION: Duration = Duration::from_secs(25 * 60);
-const BREAK_DURATION: Duration = Duration::from_secs(5 * 60);
-const MESSAGE_VISIBLE_FOR: Duration = Duration::from_secs(4);
+Options:
+  --file <path>  Task list to use (overrides $POMODORO_FILE, default todo_list.txt)
+  --read-only    Presentation mode: timers work, list editing is disabled
+  --no-save      Dry run: load the task list but never write it back
+  --debug        F9 ends the current work session or break immediately
+  -h, --help     Show this help";
 
-struct Task {
-    name: String,
-    language: String,
-    pomodoro_state: PomodoroState,
-    pomodoro_start: Option<Instant>,
-    completed_pomodoros: u32,
-}
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+const TASK_SPARKLINE_DAYS: usize = 14;
 
-struct App {
-    todos: Vec<Task>,
-    input: String,
-    language_input: String,
-    selected_index: usize,
-    input_mode: InputMode,
-    cursor_position: usize,
-    status_message: Option<(String, Instant)>,
+/// Exclusive claim on a data file so two instances can't overwrite each
+/// other's saves. Released when dropped.
+struct DataLock {
+    path: PathBuf,
 }
 
-impl App {
-    fn new() -> Self {
-        Self {
-            todos: load_todos(),
-            input: String::new(),
-            language_input: String::new(),
-            selected_index: 0,
-            input_mode: InputMode::NoTyping,
-            cursor_position: 0,
-            status_message: None,
-        }
-    }
-
-    fn start_pomodoro(&mut self) {
-        if self.todos.is_empty() {
-            return;
-        }
-        let task = &mut self.todos[self.selected_index];
-        task.pomodoro_state = PomodoroState::Work;
-        task.pomodoro_start = Some(Instant::now());
-        self.status_message = Some((
-            format!("Started focus on '{}'. Stay sharp!", task.name),
-            Instant::now(),
-        ));
-    }
-
-    fn update_pomodoro(&mut self) {
-        if self.todos.is_empty() {
-            return;
-        }
-
-        let task = &mut self.todos[self.selected_index];
-
-        if let Some(start) = task.pomodoro_start {
-            let elapsed = start.elapsed();
-
-            match task.pomodoro_state {
-                PomodoroState::Work if elapsed >= WORK_DURATION => {
-                    task.pomodoro_state = PomodoroState::Break;
-                    task.pomodoro_start = Some(Instant::now());
-                    self.status_message = Some((
-                        format!("Work session done! Take a break, {}.", task.name),
-                        Instant::now(),
-                    ));
-                    task.completed_pomodoros += 1;
+impl DataLock {
+    fn acquire(data_path: &str) -> io::Result<Self> {
+        let path = PathBuf::from(format!("{}.lock", data_path));
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", process::id())?;
+                    return Ok(Self { path });
                 }
-                PomodoroState::Break if elapsed >= BREAK_DURATION => {
-                    task.pomodoro_state = PomodoroState::Idle;
-                    task.pomodoro_start = None;
-                    self.status_message = Some((
-                        "Break finished. Ready for another round?".to_string(),
-                        Instant::now(),
-                    ));
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    let holder = fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|pid| pid.trim().parse::<u32>().ok());
+                    if let Some(pid) = holder.filter(|&pid| process_alive(pid)) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::WouldBlock,
+                            format!(
+                                "{} is already open in another instance (pid {}); \
+                                 if it isn't, delete {} and try again",
+                                data_path,
+                                pid,
+                                path.display()
+                            ),
+                        ));
+                    }
+                    // Left behind by an instance that didn't exit cleanly.
+                    fs::remove_file(&path)?;
                 }
-                _ => {}
+                Err(err) => return Err(err),
             }
         }
+        Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!("could not lock {}", data_path),
+        ))
     }
+}
 
-    fn status_message(&mut self) -> Option<String> {
-        if let Some((message, timestamp)) = &self.status_message {
-            if timestamp.elapsed() < MESSAGE_VISIBLE_FOR {
-                return Some(message.clone());
-            }
-        }
-        self.status_message = None;
-        None
+impl Drop for DataLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
     }
+}
 
-    fn pomodoro_overview(&self) -> (String, f64, Color) {
-        if self.todos.is_empty() {
-            return ("No tasks available".to_string(), 0.0, Color::DarkGray);
-        }
-
-        let task = &self.todos[self.selected_index];
-
-        if let Some(start) = task.pomodoro_start {
-            let elapsed = start.elapsed();
-            let (phase, duration, color) = match task.pomodoro_state {
-                PomodoroState::Work => ("Focus", WORK_DURATION, Color::LightGreen),
-                PomodoroState::Break => ("Break", BREAK_DURATION, Color::LightBlue),
-                PomodoroState::Idle => {
-                    return (
-                        "Pomodoro paused. Press 'p' to resume.".to_string(),
-                        0.0,
-                        Color::Gray,
-                    )
-                }
-            };
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks whether the process exists.
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
 
-            let remaining = duration
-                .checked_sub(elapsed)
-                .unwrap_or_else(|| Duration::from_secs(0));
-            let progress = (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0);
-
-            (
-                format!(
-                    "{} — {:02}:{:02} left",
-                    phase,
-                    remaining.as_secs() / 60,
-                    remaining.as_secs() % 60
-                ),
-                progress,
-                color,
-            )
-        } else {
-            (
-                "Press 'p' to start the pomodoro for this task.".to_string(),
-                0.0,
-                Color::Gray,
-            )
-        }
+/// Asks `tasklist`, whose CSV rows quote the pid; if it can't be run, the
+/// lock is assumed to be held.
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    let filter = format!("PID eq {}", pid);
+    match process::Command::new("tasklist")
+        .args(["/FI", &filter, "/FO", "CSV", "/NH"])
+        .output()
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)),
+        Err(_) => true,
     }
+}
 
-    fn handle_input(&mut self, c: char) {
-        match self.input_mode {
-            InputMode::Task => {
-                self.input.push(c);
-                self.cursor_position += 1;
-            }
-            InputMode::Language => {
-                self.language_input.push(c);
-                self.cursor_position += 1;
-            }
-            InputMode::NoTyping => {}
-        }
-    }
+#[cfg(not(any(unix, windows)))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
 
-    fn handle_backspace(&mut self) {
-        match self.input_mode {
-            InputMode::Task => {
-                if !self.input.is_empty() {
-                    self.input.pop();
-                    self.cursor_position = self.cursor_position.saturating_sub(1);
-                }
-            }
-            InputMode::Language => {
-                if !self.language_input.is_empty() {
-                    self.language_input.pop();
-                    self.cursor_position = self.cursor_position.saturating_sub(1);
+/// Command-line options.
+#[derive(Default)]
+struct Args {
+    file: Option<String>,
+    read_only: bool,
+    no_save: bool,
+    debug: bool,
+    help: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--file" => {
+                    parsed.file = Some(args.next().ok_or("--file needs a path")?);
                 }
+                "--read-only" => parsed.read_only = true,
+                "--no-save" => parsed.no_save = true,
+                "--debug" => parsed.debug = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => match arg.strip_prefix("--file=") {
+                    Some(path) => parsed.file = Some(path.to_string()),
+                    None => return Err(format!("unknown argument '{}'", arg)),
+                },
             }
-            InputMode::NoTyping => {}
         }
+        Ok(parsed)
     }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("todo-tui: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    // Locked before App::new, which may already save the list.
+    let data_path = App::resolve_data_path(args.file);
+    let _lock = match DataLock::acquire(&data_path) {
+        Ok(lock) => lock,
+        Err(err) => {
+            eprintln!("todo-tui: {}", err);
+            process::exit(1);
+        }
+    };
+    let mut app = App::new(
+        data_path,
+        Mode {
+            read_only: args.read_only,
+            no_save: args.no_save,
+            debug: args.debug,
+        },
+        Clock::System,
+    );
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-
-    let mut app = App::new();
+    let mut list_area = Rect::default();
 
     loop {
+        app.clamp_selection();
         terminal.draw(|f| {
+            let locale = app.settings.locale;
+            let area = f.area();
+            if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+                // Nothing is clickable while the layout is hidden.
+                list_area = Rect::default();
+                let message = Paragraph::new(vec![
+                    Line::from(Span::styled(
+                        locale.text(Text::EnlargeTerminal),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(format!(
+                        "{}x{} needed, currently {}x{}",
+                        MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+                    )),
+                ])
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+                let middle = area.height.saturating_sub(2) / 2;
+                let rect = Rect::new(area.x, area.y + middle, area.width, area.height - middle);
+                f.render_widget(message, rect);
+                return;
+            }
+            if app.zen && !app.is_typing() {
+                list_area = Rect::default();
+                render_zen(f, &mut app);
+                return;
+            }
+
+            // The input box only takes its full height while something is being typed.
+            let input_height = match app.input_mode {
+                InputMode::Form => FORM_FIELDS.len() as u16 + 3,
+                InputMode::Minutes
+                | InputMode::Checklist
+                | InputMode::Project
+                | InputMode::Distraction
+                | InputMode::Split
+                | InputMode::Intent
+                | InputMode::Import
+                | InputMode::Command => 4,
+                InputMode::NoTyping => 1,
+            };
+            let screen = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(u16::from(app.error_banner.is_some())),
+                    Constraint::Min(0),
+                ])
+                .split(f.area());
+            if let Some(error) = &app.error_banner {
+                let banner = Paragraph::new(Line::from(Span::styled(
+                    format!(" ⚠ {}", error),
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::Red)
+                        .add_modifier(Modifier::BOLD),
+                )))
+                .style(Style::default().bg(Color::Red));
+                f.render_widget(banner, screen[0]);
+            }
             let outer = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
                     Constraint::Length(3),
                     Constraint::Min(5),
-                    Constraint::Length(7),
+                    Constraint::Length(input_height),
                 ])
-                .split(f.area());
+                .split(screen[1]);
 
             let main_sections = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
                 .split(outer[1]);
 
+            // The running session's intent gets its own line in the overview.
+            let intent = app
+                .todos
+                .get(app.timer_index())
+                .filter(|task| matches!(task.pomodoro_state, PomodoroState::Work))
+                .and_then(|task| task.intent.clone());
+            // The controls line wraps, so the overview grows to keep it whole.
+            let controls_width = Line::from(vec![
+                Span::raw(locale.text(Text::Controls)),
+                Span::raw(&app.controls_hint),
+            ])
+            .width();
+            let controls_rows = u16::try_from(
+                controls_width
+                    .div_ceil(usize::from(main_sections[1].width.saturating_sub(2)).max(1))
+                    .max(1),
+            )
+            .unwrap_or(u16::MAX);
             let pomodoro_sections = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
                     Constraint::Length(3),
-                    Constraint::Length(3),
+                    Constraint::Length(if app.settings.daily_goal.is_some() {
+                        3
+                    } else {
+                        0
+                    }),
+                    Constraint::Length(
+                        controls_rows.saturating_add(3 + u16::from(intent.is_some())),
+                    ),
+                    Constraint::Length(4),
                     Constraint::Min(3),
                 ])
                 .split(main_sections[1]);
 
             let todo_items: Vec<ListItem> = app
-                .todos
-                .iter()
-                .enumerate()
-                .map(|(i, task)| {
+                .list_rows()
+                .into_iter()
+                .map(|row| {
+                    let i = match row {
+                        ListRow::Header(header) => {
+                            return ListItem::new(Line::from(Span::styled(
+                                format!("── {} ──", header),
+                                Style::default()
+                                    .fg(Color::DarkGray)
+                                    .add_modifier(Modifier::BOLD),
+                            )));
+                        }
+                        ListRow::Task(i) => i,
+                    };
+                    let task = &app.todos[i];
                     let (state_label, color) = match task.pomodoro_state {
-                        PomodoroState::Idle => ("Idle", Color::Gray),
-                        PomodoroState::Work => ("Focus", Color::LightGreen),
-                        PomodoroState::Break => ("Break", Color::LightBlue),
+                        _ if task.paused.is_some() => (Text::StatePaused, Color::Gray),
+                        PomodoroState::Idle => (Text::StateIdle, Color::Gray),
+                        PomodoroState::Work => (Text::StateFocus, Color::LightGreen),
+                        PomodoroState::Break => (Text::StateBreak, Color::LightBlue),
+                        PomodoroState::Stopwatch => (Text::StateStopwatch, Color::LightYellow),
                     };
+                    let state_label = locale.text(state_label);
 
-                    let primary = format!("{} · {}", task.name, task.language);
-                    let secondary = format!(
-                        "Status: {} | Completed: {}",
-                        state_label, task.completed_pomodoros
+                    // Marks tasks whose timer is ticking right now.
+                    let running = if task.pomodoro_start.is_some() {
+                        "▶ "
+                    } else {
+                        ""
+                    };
+                    let pin = if task.pinned { "📌 " } else { "" };
+                    let done = if task.done { "✓ " } else { "" };
+                    let done = if task.recurring {
+                        format!("🔁 {}", done)
+                    } else {
+                        done.to_string()
+                    };
+                    let label = task.label.map_or(String::new(), |l| format!("{} ", l));
+                    let primary = format!(
+                        "{}{}{}{}{} · {}",
+                        running, pin, done, label, task.name, task.language
                     );
+                    let completed = match task.estimated_pomodoros {
+                        Some(estimate) => format!("{}/{}", task.completed_pomodoros, estimate),
+                        None => task.completed_pomodoros.to_string(),
+                    };
+                    let lines = if let Some(template) = &app.settings.list_format {
+                        let row = fill_template(template, |key| match key {
+                            "name" => Some(task.name.clone()),
+                            "language" => Some(task.language.clone()),
+                            "state" => Some(state_label.to_string()),
+                            "completed" => Some(task.completed_pomodoros.to_string()),
+                            "estimate" => Some(
+                                task.estimated_pomodoros
+                                    .map_or("-".to_string(), |e| e.to_string()),
+                            ),
+                            "priority" => Some(task.priority.label().to_string()),
+                            "label" => Some(task.label.unwrap_or("").to_string()),
+                            "pin" => Some(if task.pinned { "📌" } else { "" }.to_string()),
+                            _ => None,
+                        });
+                        vec![Line::from(Span::styled(
+                            format!("{}{}", running, row),
+                            Style::default().fg(color),
+                        ))]
+                    } else if app.compact_list {
+                        vec![Line::from(vec![
+                            Span::raw(primary),
+                            Span::styled(
+                                format!(" · {} · {}", state_label, completed),
+                                Style::default().fg(color),
+                            ),
+                        ])]
+                    } else {
+                        let secondary = format!(
+                            "{}: {} | {}: {} | {}: {}",
+                            locale.text(Text::Status),
+                            state_label,
+                            locale.text(Text::Completed),
+                            completed,
+                            locale.text(Text::Priority),
+                            task.priority.label()
+                        );
+                        vec![
+                            Line::from(primary),
+                            Line::from(Span::styled(secondary, Style::default().fg(color))),
+                        ]
+                    };
 
-                    let lines = vec![
-                        Line::from(primary),
-                        Line::from(Span::styled(secondary, Style::default().fg(color))),
-                    ];
-
-                    let mut list_item = ListItem::new(lines);
+                    // The selection is bold yellow and a task with a running or
+                    // paused timer is underlined, so the two stay apart when
+                    // they are different tasks.
+                    let mut style = Style::default();
                     if i == app.selected_index {
-                        list_item = list_item.style(
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        );
+                        style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+                    }
+                    if !matches!(task.pomodoro_state, PomodoroState::Idle) {
+                        style = style.add_modifier(Modifier::UNDERLINED);
                     }
-                    list_item
+                    ListItem::new(lines).style(style)
                 })
                 .collect();
 
-            let list = List::new(todo_items)
-                .block(Block::default().borders(Borders::ALL).title("To-Do List"));
+            let list =
+                List::new(todo_items).block(Block::default().borders(Borders::ALL).title(format!(
+                    "{} — {}",
+                    locale.text(Text::TodoList),
+                    app.projects[app.active_project].name
+                )));
 
             let input_title = match app.input_mode {
-                InputMode::Task => "New Task (Task Input Mode)",
-                InputMode::Language => "New Task (Language Input Mode)",
-                InputMode::NoTyping => "New Task (Press 'i' to add)",
+                InputMode::Form => locale.text(Text::TitleNewTask),
+                InputMode::Minutes => locale.text(Text::TitleMinutes),
+                InputMode::Checklist => locale.text(Text::TitleChecklist),
+                InputMode::Project => locale.text(Text::TitleProject),
+                InputMode::Distraction => locale.text(Text::TitleDistraction),
+                InputMode::Split => locale.text(Text::TitleSplit),
+                InputMode::Intent => locale.text(Text::TitleIntent),
+                InputMode::Import => locale.text(Text::TitleImport),
+                InputMode::Command => locale.text(Text::TitleCommand),
+                InputMode::NoTyping => "",
             };
 
-            let input_lines = vec![
-                Line::from(vec![
-                    Span::styled("Task:", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(format!(" {}", app.input)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Language:", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(format!(" {}", app.language_input)),
-                ]),
-                Line::from("Enter to confirm, ESC to cancel"),
-            ];
+            let input_lines = match app.input_mode {
+                InputMode::Minutes => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelMinutes)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.minutes_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintMinutes)),
+                ],
+                InputMode::Checklist => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelItem)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.checklist_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintChecklist)),
+                ],
+                InputMode::Project => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelName)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.project_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintProject)),
+                ],
+                InputMode::Distraction => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelNote)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.distraction_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintDistraction)),
+                ],
+                InputMode::Split => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelName)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.split_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintSplit)),
+                ],
+                InputMode::Intent => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelGoal)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.intent_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintIntent)),
+                ],
+                InputMode::Import => vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:", locale.text(Text::LabelFile)),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" {}", app.import_input)),
+                    ]),
+                    Line::from(locale.text(Text::HintImport)),
+                ],
+                InputMode::Command => vec![
+                    Line::from(vec![
+                        Span::styled(":", Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(app.command_input.clone()),
+                    ]),
+                    Line::from(locale.text(Text::HintCommand)),
+                ],
+                _ => {
+                    let typing = matches!(app.input_mode, InputMode::Form);
+                    let mut lines: Vec<Line> = FORM_FIELDS
+                        .iter()
+                        .enumerate()
+                        .map(|(i, label)| {
+                            let focused = typing && i == app.form.focused;
+                            let value = &app.form.values[i];
+                            let placeholder = match i {
+                                FIELD_PRIORITY => "high / normal / low",
+                                FIELD_ESTIMATE => locale.text(Text::EstimatePlaceholder),
+                                _ => "",
+                            };
+                            let value_span = if value.is_empty() {
+                                Span::styled(
+                                    format!(" {}", placeholder),
+                                    Style::default().fg(Color::DarkGray),
+                                )
+                            } else {
+                                Span::raw(format!(" {}", value))
+                            };
+                            Line::from(vec![
+                                Span::raw(if focused { "› " } else { "  " }),
+                                Span::styled(
+                                    format!("{}:", locale.text(*label)),
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                ),
+                                value_span,
+                            ])
+                        })
+                        .collect();
+                    lines.push(Line::from(locale.text(Text::HintForm)));
+                    lines
+                }
+            };
 
-            let input_box = Paragraph::new(input_lines)
-                .style(Style::default().fg(Color::Yellow))
-                .block(Block::default().borders(Borders::ALL).title(input_title));
+            let input_box = if matches!(app.input_mode, InputMode::NoTyping) {
+                Paragraph::new(Line::from(Span::styled(
+                    " Press 'i' to add a task",
+                    Style::default().fg(Color::DarkGray),
+                )))
+            } else {
+                Paragraph::new(input_lines)
+                    .style(Style::default().fg(Color::Yellow))
+                    .block(Block::default().borders(Borders::ALL).title(input_title))
+            };
 
             let (status_text, progress, color) = app.pomodoro_overview();
+            // Briefly invert the gauge when a work session completes.
+            let flashing = app.flash_until.is_some_and(|until| app.clock.now() < until);
+            let background = app.background();
+            let (gauge_fg, gauge_bg) = if flashing {
+                (background, color)
+            } else {
+                (color, background)
+            };
             let gauge = Gauge::default()
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title("Pomodoro Progress"),
+                        .title(locale.text(Text::PomodoroProgress)),
                 )
                 .gauge_style(
                     Style::default()
-                        .fg(color)
-                        .bg(Color::Black)
+                        .fg(gauge_fg)
+                        .bg(gauge_bg)
                         .add_modifier(Modifier::BOLD),
                 )
                 .label(status_text)
                 .ratio(progress);
 
+            let goal = app.settings.daily_goal.unwrap_or(0);
+            let done_today = app.pomodoros_today();
+            let goal_gauge = Gauge::default()
+                .block(Block::default().borders(Borders::ALL))
+                .gauge_style(
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .bg(background)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .label(format!(
+                    "{} {}/{}",
+                    locale.text(Text::DailyGoal),
+                    done_today,
+                    goal
+                ))
+                .ratio((done_today as f64 / goal.max(1) as f64).min(1.0));
+
             let mut info_lines = vec![Line::from(vec![
                 Span::styled(
-                    "Controls:",
+                    locale.text(Text::Controls),
                     Style::default()
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::raw("  i=add task  ↑/↓=navigate  p=start timer  del=remove  q=quit"),
+                Span::raw(app.controls_hint.clone()),
             ])];
 
-            if let Some(message) = app.status_message() {
+            if let Some(intent) = intent {
+                info_lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{}: ", locale.text(Text::LabelGoal)),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(intent, Style::default().fg(Color::LightGreen)),
+                ]));
+            }
+            if let Some(confirm) = &app.pending_confirm {
+                info_lines.push(Line::from(Span::styled(
+                    confirm.prompt(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )));
+            } else if let Some(message) = app.status_message() {
                 info_lines.push(Line::from(Span::styled(
                     message,
                     Style::default()
@@ -327,49 +653,182 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )));
             }
 
-            let info_box = Paragraph::new(info_lines).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Session Overview"),
+            let info_title = format!(
+                "{} — {}: {}",
+                locale.text(Text::SessionOverview),
+                locale.text(Text::Today),
+                format_hours_minutes(app.focus_time_today())
             );
+            let info_box = Paragraph::new(info_lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title(info_title));
 
-            let summary_lines = vec![
+            let mut summary_lines = vec![
                 Line::from(vec![
                     Span::styled(
-                        "Selected Task:",
+                        locale.text(Text::SelectedTask),
                         Style::default().add_modifier(Modifier::BOLD),
                     ),
-                    Span::raw(" (press p to begin)"),
+                    Span::raw(locale.text(Text::PressToBegin)),
                 ]),
-                if app.todos.is_empty() {
-                    Line::from("No task selected")
-                } else {
-                    let task = &app.todos[app.selected_index];
-                    Line::from(format!(
-                        "{} | {} | Completed focus sessions: {}",
-                        task.name, task.language, task.completed_pomodoros
-                    ))
+                match app.todos.get(app.selected_index) {
+                    Some(task) => Line::from(format!(
+                        "{} | {} | {}: {}",
+                        task.name,
+                        task.language,
+                        locale.text(Text::CompletedSessions),
+                        task.completed_pomodoros
+                    )),
+                    None => Line::from(locale.text(Text::NoTaskSelected)),
                 },
             ];
+            if let Some(task) = app.todos.get(app.selected_index) {
+                summary_lines.push(Line::from(Span::styled(
+                    format!(
+                        "{} {} · {}: {}",
+                        locale.text(Text::Added),
+                        format_ago(task.created_at),
+                        locale.text(Text::Interruptions),
+                        task.interruptions
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                if let Some(left) = task.time_to_finish(app.settings.long_break_every) {
+                    summary_lines.push(Line::from(Span::styled(
+                        format!(
+                            "~{} {}",
+                            format_hours_minutes(left),
+                            locale.text(Text::ToFinish)
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+                let lifetime = task.lifetime_pomodoros + task.completed_pomodoros;
+                if task.recurring {
+                    summary_lines.push(Line::from(Span::styled(
+                        format!(
+                            "{} · {}: {} {}",
+                            locale.text(Text::RecurringDaily),
+                            locale.text(Text::Lifetime),
+                            lifetime,
+                            locale.text(Text::Pomodoros)
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                } else if task.lifetime_pomodoros > 0 {
+                    summary_lines.push(Line::from(Span::styled(
+                        format!(
+                            "{}: {} {}",
+                            locale.text(Text::Lifetime),
+                            lifetime,
+                            locale.text(Text::Pomodoros)
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+
+            // Approximate wrapped height so scrolling stops at the last line.
+            let summary_area = pomodoro_sections[3];
+            let inner_width = usize::from(summary_area.width.saturating_sub(2)).max(1);
+            let inner_height = summary_area.height.saturating_sub(2);
+            let wrapped_height: usize = summary_lines
+                .iter()
+                .map(|line| line.width().div_ceil(inner_width).max(1))
+                .sum();
+            let max_scroll = u16::try_from(wrapped_height)
+                .unwrap_or(u16::MAX)
+                .saturating_sub(inner_height);
+            app.snapshot_scroll = app.snapshot_scroll.min(max_scroll);
+            let summary_title = if max_scroll > 0 {
+                locale.text(Text::TaskSnapshotScroll)
+            } else {
+                locale.text(Text::TaskSnapshot)
+            };
+
+            let summary_box = Paragraph::new(summary_lines)
+                .wrap(Wrap { trim: true })
+                .scroll((app.snapshot_scroll, 0))
+                .block(Block::default().borders(Borders::ALL).title(summary_title));
 
-            let summary_box = Paragraph::new(summary_lines).block(
+            let checklist_lines: Vec<Line> = match app.todos.get(app.selected_index) {
+                Some(task) if !task.checklist.is_empty() => task
+                    .checklist
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (item, done))| {
+                        let (mark, style) = if *done {
+                            (
+                                "[x]",
+                                Style::default()
+                                    .fg(Color::DarkGray)
+                                    .add_modifier(Modifier::CROSSED_OUT),
+                            )
+                        } else {
+                            ("[ ]", Style::default())
+                        };
+                        Line::from(Span::styled(format!("{} {} {}", i + 1, mark, item), style))
+                    })
+                    .collect(),
+                Some(_) => vec![Line::from(locale.text(Text::NoChecklistItems))],
+                None => vec![Line::from(locale.text(Text::NoTaskSelected))],
+            };
+
+            let checklist_box = Paragraph::new(checklist_lines).block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Task Snapshot"),
+                    .title(locale.text(Text::Checklist)),
             );
 
-            let header = Paragraph::new(vec![Line::from(vec![
+            let mut header_spans = vec![
                 Span::styled(
                     "⚡ Pomodoro Control Center",
                     Style::default()
                         .fg(Color::LightMagenta)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::raw(" — Stay focused and track your progress"),
-            ])])
-            .block(
+                Span::raw(locale.text(Text::Tagline)),
+            ];
+            if let Some(task) = app.active_task.and_then(|i| app.todos.get(i)) {
+                header_spans.push(Span::raw("  "));
+                header_spans.push(Span::styled(
+                    format!("▶ {}", task.name),
+                    Style::default()
+                        .fg(Color::LightGreen)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if app.focus_lock {
+                header_spans.push(Span::raw("  "));
+                header_spans.push(Span::styled(
+                    "🔒 FOCUS LOCK",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            }
+            if app.read_only {
+                header_spans.push(Span::raw("  "));
+                header_spans.push(Span::styled(
+                    " READ-ONLY ",
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if app.no_save {
+                header_spans.push(Span::raw("  "));
+                header_spans.push(Span::styled(
+                    " NOT SAVING ",
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            let header = Paragraph::new(vec![Line::from(header_spans)]).block(
                 Block::default()
-                    .style(Style::default().bg(Color::Black))
+                    .style(Style::default().fg(app.foreground()).bg(background))
                     .title(Span::styled(
                         " Focus Mode ",
                         Style::default()
@@ -380,128 +839,760 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             );
 
             f.render_widget(header, outer[0]);
-            f.render_widget(list, main_sections[0]);
-            f.render_widget(gauge, pomodoro_sections[0]);
-            f.render_widget(info_box, pomodoro_sections[1]);
-            f.render_widget(summary_box, pomodoro_sections[2]);
+            match app.view {
+                View::Tasks => {
+                    list_area = main_sections[0];
+                    f.render_widget(list, main_sections[0]);
+                    f.render_widget(gauge, pomodoro_sections[0]);
+                    if goal > 0 {
+                        f.render_widget(goal_gauge, pomodoro_sections[1]);
+                    }
+                    f.render_widget(info_box, pomodoro_sections[2]);
+                    match app.todos.get(app.selected_index) {
+                        Some(task) if app.task_sparkline => {
+                            let block = Block::default().borders(Borders::ALL).title(format!(
+                                "{} — last {} days (H to go back)",
+                                task.name, TASK_SPARKLINE_DAYS
+                            ));
+                            if task.history.is_empty() {
+                                f.render_widget(block, pomodoro_sections[3]);
+                            } else {
+                                let data: Vec<u64> =
+                                    sessions_per_day(&task.history, TASK_SPARKLINE_DAYS)
+                                        .into_iter()
+                                        .map(u64::from)
+                                        .collect();
+                                let sparkline = Sparkline::default()
+                                    .block(block)
+                                    .data(&data)
+                                    .style(Style::default().fg(Color::LightGreen));
+                                f.render_widget(sparkline, pomodoro_sections[3]);
+                            }
+                        }
+                        _ => f.render_widget(summary_box, pomodoro_sections[3]),
+                    }
+                    f.render_widget(checklist_box, pomodoro_sections[4]);
+                }
+                View::Distractions => {
+                    let lines: Vec<Line> = if app.distractions.is_empty() {
+                        vec![Line::from(locale.text(Text::NoDistractions))]
+                    } else {
+                        app.distractions
+                            .iter()
+                            .enumerate()
+                            .map(|(i, note)| Line::from(format!("{:>2}. {}", i + 1, note)))
+                            .collect()
+                    };
+                    let distractions_box = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::DistractionsTitle)),
+                    );
+                    f.render_widget(distractions_box, outer[1]);
+                }
+                View::Help => {
+                    // Column-major grid of equal-width entries.
+                    let inner_width = usize::from(outer[1].width.saturating_sub(2));
+                    let entry_width = app
+                        .key_help
+                        .iter()
+                        .map(|entry| entry.chars().count())
+                        .max()
+                        .unwrap_or(0)
+                        + 3;
+                    let columns = (inner_width / entry_width).max(1);
+                    let rows = app.key_help.len().div_ceil(columns);
+                    let lines: Vec<Line> = (0..rows)
+                        .map(|row| {
+                            let cells: String = app
+                                .key_help
+                                .iter()
+                                .skip(row)
+                                .step_by(rows)
+                                .map(|entry| {
+                                    let pad = entry_width - entry.chars().count();
+                                    format!("{}{}", entry, " ".repeat(pad))
+                                })
+                                .collect();
+                            Line::from(cells)
+                        })
+                        .collect();
+                    let help_box = Paragraph::new(lines).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::AllKeys)),
+                    );
+                    f.render_widget(help_box, outer[1]);
+                }
+                View::Archive => {
+                    let lines: Vec<Line> = if app.archived.is_empty() {
+                        vec![Line::from(locale.text(Text::NoArchived))]
+                    } else {
+                        app.archived
+                            .iter()
+                            .enumerate()
+                            .map(|(i, task)| {
+                                let row = format!(
+                                    "{} · {} · {} {} · {} {}",
+                                    task.name,
+                                    task.language,
+                                    task.completed_pomodoros,
+                                    locale.text(Text::Pomodoros),
+                                    format_hours_minutes(task.total_focus_time),
+                                    locale.text(Text::Focused)
+                                );
+                                if i == app.archive_index {
+                                    Line::from(Span::styled(
+                                        format!("> {}", row),
+                                        Style::default()
+                                            .fg(Color::Yellow)
+                                            .add_modifier(Modifier::BOLD),
+                                    ))
+                                } else {
+                                    Line::from(format!("  {}", row))
+                                }
+                            })
+                            .collect()
+                    };
+                    let archive_box = Paragraph::new(lines).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::ArchiveTitle)),
+                    );
+                    f.render_widget(archive_box, outer[1]);
+                }
+                View::Settings => {
+                    let mut settings_lines: Vec<Line> = app
+                        .settings
+                        .rows()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, row)| {
+                            if i == app.settings_index {
+                                Line::from(Span::styled(
+                                    format!("> {}", row),
+                                    Style::default()
+                                        .fg(Color::Yellow)
+                                        .add_modifier(Modifier::BOLD),
+                                ))
+                            } else {
+                                Line::from(format!("  {}", row))
+                            }
+                        })
+                        .collect();
+                    settings_lines.push(Line::from(""));
+                    settings_lines.push(Line::from(
+                        "↑/↓=select  ←/→=change (plays a preview)  o=back",
+                    ));
+
+                    let settings_box = Paragraph::new(settings_lines).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::Settings)),
+                    );
+                    f.render_widget(settings_box, outer[1]);
+                }
+                View::Stats => {
+                    let stats_sections = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(8),
+                            Constraint::Length(5),
+                            Constraint::Min(3),
+                        ])
+                        .split(outer[1]);
+
+                    let per_day = app.pomodoros_per_day(7);
+                    let today = local_day(SystemTime::now());
+                    let sparkline_data: Vec<u64> = per_day.iter().map(|&c| c as u64).collect();
+                    let sparkline = Sparkline::default()
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .title(locale.text(Text::LastSevenDays)),
+                        )
+                        .data(&sparkline_data)
+                        .style(Style::default().fg(Color::LightGreen));
+
+                    let mut day_lines: Vec<Line> = per_day
+                        .iter()
+                        .enumerate()
+                        .map(|(i, count)| {
+                            let day = today - (per_day.len() - 1 - i) as i64;
+                            Line::from(format!(
+                                "{}  {:>3} {}",
+                                weekday_name(day),
+                                count,
+                                locale.text(Text::Pomodoros)
+                            ))
+                        })
+                        .collect();
+                    let (focus, rest) = app.focus_and_break_time();
+                    let tracked = (focus + rest).as_secs_f64();
+                    day_lines.insert(0, Line::from(""));
+                    day_lines.insert(
+                        0,
+                        Line::from(Span::styled(
+                            format!(
+                                "{}: {}",
+                                locale.text(Text::LifetimeFocus),
+                                format_hours_minutes(app.lifetime_focus_time())
+                            ),
+                            Style::default()
+                                .fg(Color::LightMagenta)
+                                .add_modifier(Modifier::BOLD),
+                        )),
+                    );
+                    day_lines.insert(
+                        0,
+                        Line::from(if tracked > 0.0 {
+                            format!(
+                                "{}: {:.0}% {} / {:.0}% {}",
+                                locale.text(Text::FocusRatio),
+                                focus.as_secs_f64() / tracked * 100.0,
+                                locale.text(Text::FocusShare),
+                                rest.as_secs_f64() / tracked * 100.0,
+                                locale.text(Text::BreakShare)
+                            )
+                        } else {
+                            format!(
+                                "{}: {}",
+                                locale.text(Text::FocusRatio),
+                                locale.text(Text::NoTimeTracked)
+                            )
+                        }),
+                    );
+                    let days_box = Paragraph::new(day_lines).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::PerDay)),
+                    );
+
+                    let mut languages: Vec<(String, LanguageStats)> =
+                        app.language_stats().into_iter().collect();
+                    languages
+                        .sort_by(|a, b| b.1.focus_time.cmp(&a.1.focus_time).then(a.0.cmp(&b.0)));
+                    let language_rows: Vec<Row> = languages
+                        .into_iter()
+                        .map(|(language, stats)| {
+                            Row::new(vec![
+                                Cell::from(language),
+                                Cell::from(stats.tasks.to_string()),
+                                Cell::from(stats.pomodoros.to_string()),
+                                Cell::from(format_hours_minutes(stats.focus_time)),
+                            ])
+                        })
+                        .collect();
+                    let language_table = Table::new(
+                        language_rows,
+                        [
+                            Constraint::Min(12),
+                            Constraint::Length(6),
+                            Constraint::Length(10),
+                            Constraint::Length(10),
+                        ],
+                    )
+                    .header(
+                        Row::new(vec![
+                            locale.text(Text::FieldLanguage),
+                            locale.text(Text::ColumnTasks),
+                            locale.text(Text::ColumnPomodoros),
+                            locale.text(Text::StateFocus),
+                        ])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::ByLanguage)),
+                    );
+
+                    let per_hour = app.pomodoros_per_hour();
+                    let busiest = per_hour.iter().copied().max().unwrap_or(0);
+                    let heat_cells: Vec<Span> = per_hour
+                        .iter()
+                        .map(|&count| {
+                            let shade = match (count, busiest) {
+                                (0, _) => "··",
+                                (c, b) if c * 3 <= b => "░░",
+                                (c, b) if c * 3 <= b * 2 => "▒▒",
+                                (c, b) if c < b => "▓▓",
+                                _ => "██",
+                            };
+                            Span::styled(shade, Style::default().fg(Color::LightGreen))
+                        })
+                        .collect();
+                    let hour_axis: String = (0..24)
+                        .step_by(3)
+                        .map(|hour| format!("{:<6}", hour))
+                        .collect();
+                    let peak_line = match per_hour.iter().position(|&c| c == busiest) {
+                        Some(hour) if busiest > 0 => format!(
+                            "{} {:02}:00-{:02}:59 ({})",
+                            locale.text(Text::PeakHour),
+                            hour,
+                            hour,
+                            busiest
+                        ),
+                        _ => locale.text(Text::NoPomodorosYet).to_string(),
+                    };
+                    let hour_box = Paragraph::new(vec![
+                        Line::from(heat_cells),
+                        Line::from(hour_axis),
+                        Line::from(peak_line),
+                    ])
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(locale.text(Text::ByHour)),
+                    );
+
+                    let lower_sections = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                        .split(stats_sections[2]);
+
+                    f.render_widget(sparkline, stats_sections[0]);
+                    f.render_widget(hour_box, stats_sections[1]);
+                    f.render_widget(days_box, lower_sections[0]);
+                    f.render_widget(language_table, lower_sections[1]);
+                }
+            }
             f.render_widget(input_box, outer[2]);
+
+            // Place the terminal cursor after the text being typed.
+            let label = |key| format!("{}: ", locale.text(key));
+            let cursor = match app.input_mode {
+                InputMode::Form => Some((
+                    app.form.focused,
+                    format!("› {}: ", locale.text(FORM_FIELDS[app.form.focused])),
+                    &app.form.values[app.form.focused],
+                )),
+                InputMode::Minutes => Some((0, label(Text::LabelMinutes), &app.minutes_input)),
+                InputMode::Checklist => Some((0, label(Text::LabelItem), &app.checklist_input)),
+                InputMode::Project => Some((0, label(Text::LabelName), &app.project_input)),
+                InputMode::Distraction => Some((0, label(Text::LabelNote), &app.distraction_input)),
+                InputMode::Split => Some((0, label(Text::LabelName), &app.split_input)),
+                InputMode::Intent => Some((0, label(Text::LabelGoal), &app.intent_input)),
+                InputMode::Import => Some((0, label(Text::LabelFile), &app.import_input)),
+                InputMode::Command => Some((0, ":".to_string(), &app.command_input)),
+                InputMode::NoTyping => None,
+            };
+            if let Some((row, prefix, value)) = cursor {
+                let before: String = prefix
+                    .chars()
+                    .chain(value.chars().take(app.cursor_position))
+                    .collect();
+                let area = outer[2];
+                let column = area.x + 1 + Span::raw(before).width() as u16;
+                f.set_cursor_position((
+                    column.min(area.right().saturating_sub(2)),
+                    area.y + 1 + row as u16,
+                ));
+            }
         })?;
 
         app.update_pomodoro();
+        app.check_idle();
+        app.check_hooks();
+        app.check_external_change();
+        app.flush_pending_save();
+        if app.farewell.is_some() {
+            app.save();
+            break;
+        }
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => break,
+        if event::poll(app.settings.tick_interval)? {
+            let event = event::read()?;
+            if let Event::Key(_) | Event::Paste(_) = event {
+                app.last_activity = app.clock.now();
+            }
+            match event {
+                Event::Key(key) if app.pending_confirm.is_some() => {
+                    let confirm = app.pending_confirm.take();
+                    match (key.code, confirm) {
+                        (KeyCode::Char('y'), Some(Confirmation::Quit)) => {
+                            app.save();
+                            break;
+                        }
+                        (KeyCode::Char('y'), Some(Confirmation::ResetStats)) => app.reset_stats(),
+                        (KeyCode::Char('y'), Some(Confirmation::StartDone(custom))) => {
+                            app.begin_work(custom)
+                        }
+                        (KeyCode::Char('y'), Some(Confirmation::Reload)) => app.reload(),
+                        (KeyCode::Char('y'), Some(Confirmation::RestartCycle)) => {
+                            app.restart_cycle()
+                        }
+                        (_, Some(Confirmation::RestartCycle)) => {
+                            app.set_status("Reopened; the pomodoro count is unchanged.")
+                        }
+                        (_, Some(Confirmation::Reload)) => app.keep_local_changes(),
+                        _ => app.set_status("Cancelled."),
+                    }
+                }
+                Event::Key(key) => match key.code {
+                    KeyCode::Up if matches!(app.view, View::Settings) => {
+                        app.settings_index = app.settings_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if matches!(app.view, View::Settings) => {
+                        app.settings_index =
+                            (app.settings_index + 1).min(app.settings.rows().len() - 1);
+                    }
+                    KeyCode::Up if matches!(app.view, View::Archive) => {
+                        app.archive_index = app.archive_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if matches!(app.view, View::Archive) => {
+                        app.archive_index =
+                            (app.archive_index + 1).min(app.archived.len().saturating_sub(1));
+                    }
+                    KeyCode::Enter if matches!(app.view, View::Archive) && !app.is_typing() => {
+                        app.restore_archived();
+                    }
+                    KeyCode::Left if matches!(app.view, View::Settings) => {
+                        app.adjust_setting(false)
+                    }
+                    KeyCode::Right if matches!(app.view, View::Settings) => {
+                        app.adjust_setting(true)
+                    }
+                    KeyCode::F(9) if app.debug && !app.is_typing() => app.finish_phase_now(),
+                    KeyCode::Char(c) if !app.is_typing() && app.keymap.contains_key(&c) => {
+                        let action = app.keymap[&c];
+                        if app.run_action(action) {
+                            break;
+                        }
+                    }
+                    KeyCode::Up | KeyCode::Down | KeyCode::Delete | KeyCode::Tab
+                        if !app.is_typing() && app.is_focus_locked() =>
+                    {
+                        app.set_status("Stay focused!");
+                    }
+                    code if !app.is_typing() && app.read_only && App::is_edit_key(code) => {
+                        app.set_status("Read-only mode: editing is disabled.");
+                    }
                     KeyCode::Esc => {
                         app.input_mode = InputMode::NoTyping;
-                        app.input.clear();
-                        app.language_input.clear();
-                        app.status_message =
-                            Some(("Creation cancelled.".to_string(), Instant::now()));
-                    }
-                    KeyCode::Char('p') => {
-                        app.start_pomodoro();
+                        app.form = TaskForm::default();
+                        app.minutes_input.clear();
+                        app.checklist_input.clear();
+                        app.project_input.clear();
+                        app.distraction_input.clear();
+                        app.split_input.clear();
+                        app.intent_input.clear();
+                        app.import_input.clear();
+                        app.command_input.clear();
+                        app.set_status("Creation cancelled.");
                     }
-                    KeyCode::Char('i') => {
-                        app.input_mode = InputMode::Task;
-                        app.cursor_position = 0;
+                    KeyCode::Char(c @ '1'..='9') if !app.is_typing() => {
+                        app.toggle_checklist_item(c as usize - '0' as usize);
                     }
                     KeyCode::Char(c) => app.handle_input(c),
                     KeyCode::Backspace => app.handle_backspace(),
+                    KeyCode::Tab if matches!(app.input_mode, InputMode::Form) => {
+                        app.next_form_field();
+                    }
+                    KeyCode::BackTab if matches!(app.input_mode, InputMode::Form) => {
+                        app.prev_form_field();
+                    }
+                    KeyCode::Tab if !app.is_typing() => app.next_project(),
                     KeyCode::Enter => match app.input_mode {
-                        InputMode::Task => {
-                            if !app.input.trim().is_empty() {
-                                app.input_mode = InputMode::Language;
-                                app.cursor_position = 0;
-                            }
-                        }
-                        InputMode::Language => {
-                            if !app.language_input.trim().is_empty() {
-                                app.todos.push(Task {
-                                    name: app.input.trim().to_string(),
-                                    language: app.language_input.trim().to_string(),
-                                    pomodoro_state: PomodoroState::Idle,
-                                    pomodoro_start: None,
-                                    completed_pomodoros: 0,
-                                });
-                                save_todos(&app.todos);
-                                app.input.clear();
-                                app.language_input.clear();
-                                app.input_mode = InputMode::NoTyping;
-                                app.cursor_position = 0;
-                                app.status_message = Some((
-                                    "New task added. Ready to focus!".to_string(),
-                                    Instant::now(),
-                                ));
+                        InputMode::Form => app.confirm_new_task(),
+                        InputMode::Minutes => app.confirm_custom_minutes(),
+                        InputMode::Checklist => app.confirm_checklist_item(),
+                        InputMode::Project => app.confirm_new_project(),
+                        InputMode::Distraction => app.confirm_distraction(),
+                        InputMode::Split => app.confirm_split(),
+                        InputMode::Intent => app.confirm_intent(),
+                        InputMode::Import => app.confirm_import(),
+                        InputMode::Command => {
+                            if app.confirm_command() {
+                                break;
                             }
                         }
                         InputMode::NoTyping => {}
                     },
-                    KeyCode::Delete if !app.todos.is_empty() => {
-                        let removed = app.todos.remove(app.selected_index);
-                        save_todos(&app.todos);
-                        if app.selected_index >= app.todos.len() && app.selected_index > 0 {
-                            app.selected_index -= 1;
-                        }
-                        app.status_message =
-                            Some((format!("Removed '{}'.", removed.name), Instant::now()));
+                    KeyCode::Delete if matches!(app.view, View::Distractions) => {
+                        app.clear_distractions();
                     }
-                    KeyCode::Up if app.selected_index > 0 => {
-                        app.selected_index -= 1;
+                    KeyCode::Delete => app.delete_selected(),
+                    KeyCode::PageUp => {
+                        app.snapshot_scroll = app.snapshot_scroll.saturating_sub(1);
                     }
-                    KeyCode::Down if app.selected_index < app.todos.len().saturating_sub(1) => {
-                        app.selected_index += 1;
+                    KeyCode::PageDown => {
+                        app.snapshot_scroll = app.snapshot_scroll.saturating_add(1);
                     }
+                    KeyCode::Up => app.move_selection(false),
+                    KeyCode::Down => app.move_selection(true),
                     _ => {}
-                }
+                },
+                Event::Mouse(mouse) => app.handle_mouse(mouse, list_area),
+                // Repaint everything at the new size on the next draw instead
+                // of diffing against a buffer laid out for the old one.
+                Event::Resize(_, _) => terminal.clear()?,
+                Event::FocusLost => app.focus_lost(),
+                Event::FocusGained => app.focus_gained(),
+                Event::Paste(text) => app.handle_paste(&text),
             }
         }
     }
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste
+    )?;
     terminal.show_cursor()?;
 
+    if let Some(farewell) = &app.farewell {
+        println!("{}", farewell);
+    }
     Ok(())
 }
 
-fn load_todos() -> Vec<Task> {
-    match fs::read_to_string("todo_list.txt") {
-        Ok(content) => content
-            .lines()
-            .map(|s| {
-                let parts: Vec<&str> = s.split(" | ").collect();
-                let completed = parts
-                    .get(2)
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .unwrap_or(0);
-                Task {
-                    name: parts[0].to_string(),
-                    language: parts.get(1).unwrap_or(&"Unknown").to_string(),
-                    pomodoro_state: PomodoroState::Idle,
-                    pomodoro_start: None,
-                    completed_pomodoros: completed,
+/// The zen layout: the selected task and its timer, centered on screen.
+fn render_zen(f: &mut Frame, app: &mut App) {
+    let message = app.status_message();
+    let footer = match (&app.pending_confirm, message) {
+        (Some(confirm), _) => confirm.prompt().to_string(),
+        (None, Some(message)) => message,
+        (None, None) => "z to leave zen mode".to_string(),
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(2),
+            Constraint::Length(5),
+            Constraint::Length(2),
+            Constraint::Min(0),
+        ])
+        .split(f.area());
+    let columns = |area: Rect| {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(15),
+                Constraint::Percentage(70),
+                Constraint::Percentage(15),
+            ])
+            .split(area)[1]
+    };
+
+    let name = app
+        .todos
+        .get(app.timer_index())
+        .map_or(app.settings.locale.text(Text::NoTaskSelected), |task| {
+            task.name.as_str()
+        });
+    let title = Paragraph::new(Line::from(Span::styled(
+        name,
+        Style::default()
+            .fg(Color::LightMagenta)
+            .add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center);
+
+    let (label, progress, color) = app.pomodoro_overview();
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL))
+        .gauge_style(
+            Style::default()
+                .fg(color)
+                .bg(app.background())
+                .add_modifier(Modifier::BOLD),
+        )
+        .label(label)
+        .ratio(progress);
+
+    let footer = Paragraph::new(Line::from(Span::styled(
+        footer,
+        Style::default().fg(Color::DarkGray),
+    )))
+    .alignment(Alignment::Center);
+
+    f.render_widget(title, columns(rows[1]));
+    f.render_widget(gauge, columns(rows[2]));
+    f.render_widget(footer, columns(rows[3]));
+}
+
+/// The parts of `App` that deal in terminal types.
+impl App {
+    /// Keys that change the task list itself rather than timers or views.
+    fn is_edit_key(code: KeyCode) -> bool {
+        matches!(code, KeyCode::Delete | KeyCode::Char('1'..='9'))
+    }
+
+    /// Clicking a task selects it; the scroll wheel moves the selection.
+    /// `list_area` is where the task list was last drawn.
+    fn handle_mouse(&mut self, mouse: MouseEvent, list_area: Rect) {
+        if !matches!(self.view, View::Tasks) || self.todos.is_empty() {
+            return;
+        }
+        if self.is_focus_locked() {
+            self.set_status("Stay focused!");
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let area = list_area;
+                let inside = mouse.column > area.x
+                    && mouse.column < area.right().saturating_sub(1)
+                    && mouse.row > area.y
+                    && mouse.row < area.bottom().saturating_sub(1);
+                if inside {
+                    // Tasks are one or two lines tall and headers one, below
+                    // the top border.
+                    let one_line = self.compact_list || self.settings.list_format.is_some();
+                    let item_height = if one_line { 1 } else { 2 };
+                    let mut line = usize::from(mouse.row - area.y - 1);
+                    for row in self.list_rows() {
+                        let height = match row {
+                            ListRow::Header(_) => 1,
+                            ListRow::Task(_) => item_height,
+                        };
+                        if line < height {
+                            if let ListRow::Task(index) = row {
+                                self.selected_index = index;
+                            }
+                            break;
+                        }
+                        line -= height;
+                    }
                 }
-            })
-            .collect(),
-        Err(_) => Vec::new(),
+            }
+            MouseEventKind::ScrollUp => self.move_selection(false),
+            MouseEventKind::ScrollDown => self.move_selection(true),
+            _ => {}
+        }
     }
-}
 
-fn save_todos(todos: &Vec<Task>) {
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open("todo_list.txt")
-        .unwrap();
-    for task in todos {
-        writeln!(
-            file,
-            "{} | {} | {}",
-            task.name, task.language, task.completed_pomodoros
-        )
-        .unwrap();
+    fn background(&self) -> Color {
+        match self.settings.theme {
+            Theme::Dark => Color::Black,
+            Theme::Light => Color::White,
+        }
+    }
+
+    /// Text drawn straight on `background`.
+    fn foreground(&self) -> Color {
+        match self.settings.theme {
+            Theme::Dark => Color::White,
+            Theme::Light => Color::Black,
+        }
+    }
+
+    fn pomodoro_overview(&self) -> (String, f64, Color) {
+        let locale = self.settings.locale;
+        if let Some(start) = self.quick_timer {
+            let elapsed = self.clock.since(start);
+            let remaining = WORK_DURATION.saturating_sub(elapsed);
+            let progress = (elapsed.as_secs_f64() / WORK_DURATION.as_secs_f64()).min(1.0);
+            let mut label = format!(
+                "{} — {} {}",
+                locale.text(Text::QuickTimer),
+                self.settings.format_countdown(remaining, WORK_DURATION),
+                locale.text(Text::Left)
+            );
+            if self.settings.show_elapsed {
+                label.push_str(&format!(
+                    " ({} {})",
+                    self.settings.format_elapsed(elapsed, WORK_DURATION),
+                    locale.text(Text::Elapsed)
+                ));
+            }
+            return (label, progress, Color::LightMagenta);
+        }
+        let Some(task) = self.todos.get(self.timer_index()) else {
+            return (locale.text(Text::NoTasks).to_string(), 0.0, Color::DarkGray);
+        };
+        let idle = (locale.text(Text::IdleHint).to_string(), 0.0, Color::Gray);
+        let Some(elapsed) = task.elapsed(self.clock.now()) else {
+            return idle;
+        };
+        let paused = task.paused.is_some();
+
+        let (phase, duration, color) = match task.pomodoro_state {
+            PomodoroState::Work => (Text::StateFocus, task.work_duration(), Color::LightGreen),
+            PomodoroState::Break if task.is_long_break(self.settings.long_break_every) => (
+                Text::LongBreak,
+                task.break_duration(self.settings.long_break_every),
+                Color::LightBlue,
+            ),
+            PomodoroState::Break => (
+                Text::StateBreak,
+                task.break_duration(self.settings.long_break_every),
+                Color::LightBlue,
+            ),
+            PomodoroState::Stopwatch => {
+                // No target to fill, so the gauge sweeps once a minute.
+                let sweep = (elapsed.as_secs() % 60) as f64 / 60.0;
+                let mut label = format!(
+                    "{} — {} {}",
+                    locale.text(Text::StateStopwatch),
+                    self.settings.time_format.format(elapsed, elapsed),
+                    locale.text(Text::Elapsed)
+                );
+                if paused {
+                    label.push_str(&format!(" — {}", locale.text(Text::PausedResume)));
+                    return (label, sweep, Color::Gray);
+                }
+                return (label, sweep, Color::LightYellow);
+            }
+            PomodoroState::Idle => return idle,
+        };
+
+        let remaining = duration
+            .checked_sub(elapsed)
+            .unwrap_or_else(|| Duration::from_secs(0));
+        let progress = (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0);
+
+        let percent = (progress * 100.0).round() as u32;
+        let phase = locale.text(phase);
+        let mut label = if self.settings.show_elapsed {
+            format!(
+                "{} — {} {} ({} {}, {}%)",
+                phase,
+                self.settings.format_countdown(remaining, duration),
+                locale.text(Text::Left),
+                self.settings.format_elapsed(elapsed, duration),
+                locale.text(Text::Elapsed),
+                percent
+            )
+        } else {
+            format!(
+                "{} — {} {} ({}%)",
+                phase,
+                self.settings.format_countdown(remaining, duration),
+                locale.text(Text::Left),
+                percent
+            )
+        };
+        if matches!(task.pomodoro_state, PomodoroState::Work)
+            && task.next_break_is_long(self.settings.long_break_every)
+        {
+            label.push_str(&format!(" — {}", locale.text(Text::NextLongBreak)));
+        }
+        if paused {
+            label.push_str(&format!(" — {}", locale.text(Text::PausedResume)));
+            return (label, progress, Color::Gray);
+        }
+        // Alternate colours each second near the end of a focus session,
+        // and say so in words for anyone who can't tell the colours apart.
+        let wrapping_up = matches!(task.pomodoro_state, PomodoroState::Work)
+            && self
+                .settings
+                .wrap_up_warning
+                .is_some_and(|warning| remaining <= warning);
+        if wrapping_up {
+            label.push_str(&format!(" — {}", locale.text(Text::WrappingUp)));
+        }
+        if wrapping_up && remaining.as_secs().is_multiple_of(2) {
+            return (label, progress, Color::LightRed);
+        }
+        (label, progress, color)
     }
 }
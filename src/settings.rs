@@ -0,0 +1,917 @@
+//! User preferences, the interface strings and settings.txt.
+
+use crate::{keymap::Action, storage::SESSION_LOG_FILE, task::LONG_BREAK_EVERY};
+use std::{fs, time::Duration};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlertSound {
+    Chime,
+    Beep,
+    Bell,
+    Off,
+}
+
+impl AlertSound {
+    const ALL: [AlertSound; 4] = [
+        AlertSound::Chime,
+        AlertSound::Beep,
+        AlertSound::Bell,
+        AlertSound::Off,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertSound::Chime => "Chime",
+            AlertSound::Beep => "Beep",
+            AlertSound::Bell => "Terminal bell",
+            AlertSound::Off => "Off",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            AlertSound::Chime => "chime",
+            AlertSound::Beep => "beep",
+            AlertSound::Bell => "bell",
+            AlertSound::Off => "off",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sound| sound.key() == key)
+    }
+
+    fn cycle(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward { index + 1 } else { index + len - 1 };
+        Self::ALL[next % len]
+    }
+
+    /// Tones as (frequency in Hz, length in ms) for the synthesized cues.
+    pub fn notes(self) -> &'static [(f32, u32)] {
+        match self {
+            AlertSound::Chime => &[(660.0, 180), (880.0, 180), (1320.0, 320)],
+            AlertSound::Beep => &[(1000.0, 120), (0.0, 80), (1000.0, 120)],
+            AlertSound::Bell | AlertSound::Off => &[],
+        }
+    }
+}
+
+/// How the gauge shows remaining or elapsed time.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimeFormat {
+    /// H:MM:SS once the session is an hour or longer, MM:SS otherwise.
+    Auto,
+    Minutes,
+    Hours,
+}
+
+impl TimeFormat {
+    const ALL: [TimeFormat; 3] = [TimeFormat::Auto, TimeFormat::Minutes, TimeFormat::Hours];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeFormat::Auto => "Auto",
+            TimeFormat::Minutes => "MM:SS",
+            TimeFormat::Hours => "H:MM:SS",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            TimeFormat::Auto => "auto",
+            TimeFormat::Minutes => "minutes",
+            TimeFormat::Hours => "hours",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.key() == key)
+    }
+
+    fn cycle(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward { index + 1 } else { index + len - 1 };
+        Self::ALL[next % len]
+    }
+
+    /// Formats `time`; `span` is the full length of the session it belongs to.
+    pub fn format(self, time: Duration, span: Duration) -> String {
+        let secs = time.as_secs();
+        let long = match self {
+            TimeFormat::Auto => span >= Duration::from_secs(60 * 60),
+            TimeFormat::Minutes => false,
+            TimeFormat::Hours => true,
+        };
+        if long {
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        } else {
+            format!("{:02}:{:02}", secs / 60, secs % 60)
+        }
+    }
+}
+
+/// Colors behind gauges and the header, to suit dark or light terminals.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.key() == key)
+    }
+
+    pub fn cycle(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward { index + 1 } else { index + len - 1 };
+        Self::ALL[next % len]
+    }
+}
+
+/// Language for the fixed interface text.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Locale {
+    English,
+    Indonesian,
+}
+
+/// Keys into the interface strings table.
+#[derive(Clone, Copy)]
+pub enum Text {
+    Tagline,
+    TodoList,
+    PomodoroProgress,
+    SessionOverview,
+    Today,
+    Controls,
+    TaskSnapshot,
+    TaskSnapshotScroll,
+    SelectedTask,
+    PressToBegin,
+    NoTaskSelected,
+    NoTasks,
+    IdleHint,
+    Checklist,
+    NoChecklistItems,
+    Settings,
+    LastSevenDays,
+    PerDay,
+    ByLanguage,
+    ByHour,
+    EnlargeTerminal,
+    DailyGoal,
+    StatePaused,
+    StateIdle,
+    StateFocus,
+    StateBreak,
+    StateStopwatch,
+    LongBreak,
+    QuickTimer,
+    Left,
+    Elapsed,
+    NextLongBreak,
+    PausedResume,
+    WrappingUp,
+    Status,
+    Completed,
+    Priority,
+    FieldTask,
+    FieldLanguage,
+    FieldEstimate,
+    EstimatePlaceholder,
+    TitleNewTask,
+    TitleMinutes,
+    TitleChecklist,
+    TitleProject,
+    TitleDistraction,
+    TitleSplit,
+    TitleIntent,
+    TitleImport,
+    TitleCommand,
+    LabelMinutes,
+    LabelItem,
+    LabelName,
+    LabelNote,
+    LabelGoal,
+    LabelFile,
+    HintForm,
+    HintMinutes,
+    HintChecklist,
+    HintProject,
+    HintDistraction,
+    HintSplit,
+    HintIntent,
+    HintImport,
+    HintCommand,
+    CompletedSessions,
+    Added,
+    Interruptions,
+    ToFinish,
+    RecurringDaily,
+    Lifetime,
+    Pomodoros,
+    Focused,
+    LifetimeFocus,
+    FocusRatio,
+    FocusShare,
+    BreakShare,
+    NoTimeTracked,
+    ColumnTasks,
+    ColumnPomodoros,
+    PeakHour,
+    NoPomodorosYet,
+    DistractionsTitle,
+    NoDistractions,
+    ArchiveTitle,
+    NoArchived,
+    AllKeys,
+}
+
+impl Locale {
+    const ALL: [Locale; 2] = [Locale::English, Locale::Indonesian];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Indonesian => "Bahasa Indonesia",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Indonesian => "id",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|locale| locale.key() == key)
+    }
+
+    fn cycle(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward { index + 1 } else { index + len - 1 };
+        Self::ALL[next % len]
+    }
+
+    /// Looks `key` up for this locale, falling back to English for anything
+    /// not translated yet.
+    pub fn text(self, key: Text) -> &'static str {
+        let translated = match self {
+            Locale::English => None,
+            Locale::Indonesian => indonesian(key),
+        };
+        translated.unwrap_or_else(|| english(key))
+    }
+}
+
+fn english(key: Text) -> &'static str {
+    match key {
+        Text::Tagline => " — Stay focused and track your progress",
+        Text::TodoList => "To-Do List",
+        Text::PomodoroProgress => "Pomodoro Progress",
+        Text::SessionOverview => "Session Overview",
+        Text::Today => "Today",
+        Text::Controls => "Controls:",
+        Text::TaskSnapshot => "Task Snapshot",
+        Text::TaskSnapshotScroll => "Task Snapshot (PgUp/PgDn to scroll)",
+        Text::SelectedTask => "Selected Task:",
+        Text::PressToBegin => " (press p to begin)",
+        Text::NoTaskSelected => "No task selected",
+        Text::NoTasks => "No tasks available",
+        Text::IdleHint => "Press 'p' to start the pomodoro for this task.",
+        Text::Checklist => "Checklist (1-9 to toggle)",
+        Text::NoChecklistItems => "No checklist items. Press u to add one.",
+        Text::Settings => "Settings",
+        Text::LastSevenDays => "Last 7 Days (press s to go back)",
+        Text::PerDay => "Per Day",
+        Text::ByLanguage => "By Language",
+        Text::ByHour => "By Hour of Day",
+        Text::EnlargeTerminal => "Please enlarge your terminal",
+        Text::DailyGoal => "Daily Goal",
+        Text::StatePaused => "Paused",
+        Text::StateIdle => "Idle",
+        Text::StateFocus => "Focus",
+        Text::StateBreak => "Break",
+        Text::StateStopwatch => "Stopwatch",
+        Text::LongBreak => "Long break",
+        Text::QuickTimer => "Quick timer",
+        Text::Left => "left",
+        Text::Elapsed => "elapsed",
+        Text::NextLongBreak => "Next: long break",
+        Text::PausedResume => "paused, press p to resume",
+        Text::WrappingUp => "wrapping up",
+        Text::Status => "Status",
+        Text::Completed => "Completed",
+        Text::Priority => "Priority",
+        Text::FieldTask => "Task",
+        Text::FieldLanguage => "Language",
+        Text::FieldEstimate => "Estimate",
+        Text::EstimatePlaceholder => "pomodoros, optional",
+        Text::TitleNewTask => "New Task (Tab/Shift+Tab to switch field)",
+        Text::TitleMinutes => "Custom Session (Minutes Input Mode)",
+        Text::TitleChecklist => "Checklist (Item Input Mode)",
+        Text::TitleProject => "New Project (Name Input Mode)",
+        Text::TitleDistraction => "Distraction (Note Input Mode)",
+        Text::TitleSplit => "Split Task (Name Input Mode)",
+        Text::TitleIntent => "What will you accomplish this session?",
+        Text::TitleImport => "Import Tasks (Path Input Mode)",
+        Text::TitleCommand => "Command Palette",
+        Text::LabelMinutes => "Minutes",
+        Text::LabelItem => "Item",
+        Text::LabelName => "Name",
+        Text::LabelNote => "Note",
+        Text::LabelGoal => "Goal",
+        Text::LabelFile => "File",
+        Text::HintForm => "Enter to add the task, ESC to cancel",
+        Text::HintMinutes => "Enter to start this session only, ESC to cancel",
+        Text::HintChecklist => "Enter to add to the selected task, ESC to cancel",
+        Text::HintProject => "Enter to create and switch to it, ESC to cancel",
+        Text::HintDistraction => {
+            "Enter to save it for later (the timer keeps running), ESC to cancel"
+        }
+        Text::HintSplit => "Enter to add it after the selected task, ESC to cancel",
+        Text::HintIntent => "Enter to start (leave empty to skip), ESC to cancel",
+        Text::HintImport => "One task name per line. Enter to import, ESC to cancel",
+        Text::HintCommand => "e.g. add, delete, stats, export, or any action name. Enter to run",
+        Text::CompletedSessions => "Completed focus sessions",
+        Text::Added => "Added",
+        Text::Interruptions => "Interruptions",
+        Text::ToFinish => "to finish",
+        Text::RecurringDaily => "Recurring daily",
+        Text::Lifetime => "Lifetime",
+        Text::Pomodoros => "pomodoros",
+        Text::Focused => "focused",
+        Text::LifetimeFocus => "Lifetime focus",
+        Text::FocusRatio => "Focus ratio",
+        Text::FocusShare => "focus",
+        Text::BreakShare => "break",
+        Text::NoTimeTracked => "no time tracked yet",
+        Text::ColumnTasks => "Tasks",
+        Text::ColumnPomodoros => "Pomodoros",
+        Text::PeakHour => "Most pomodoros finish at",
+        Text::NoPomodorosYet => "No pomodoros finished yet",
+        Text::DistractionsTitle => "Distractions (j=add  del=clear all  J=back)",
+        Text::NoDistractions => "Nothing captured. Press j during a session to jot a thought down.",
+        Text::ArchiveTitle => "Archived Tasks (↑/↓=select  enter=restore  W=back)",
+        Text::NoArchived => "Nothing archived. Press a on a task to set it aside.",
+        Text::AllKeys => "All Keys (?=back)",
+    }
+}
+
+fn indonesian(key: Text) -> Option<&'static str> {
+    Some(match key {
+        Text::Tagline => " — Tetap fokus dan pantau kemajuan Anda",
+        Text::TodoList => "Daftar Tugas",
+        Text::PomodoroProgress => "Progres Pomodoro",
+        Text::SessionOverview => "Ringkasan Sesi",
+        Text::Today => "Hari ini",
+        Text::Controls => "Kontrol:",
+        Text::TaskSnapshot => "Ringkasan Tugas",
+        Text::TaskSnapshotScroll => "Ringkasan Tugas (PgUp/PgDn untuk menggulir)",
+        Text::SelectedTask => "Tugas Terpilih:",
+        Text::PressToBegin => " (tekan p untuk mulai)",
+        Text::NoTaskSelected => "Tidak ada tugas terpilih",
+        Text::NoTasks => "Belum ada tugas",
+        Text::IdleHint => "Tekan 'p' untuk memulai pomodoro tugas ini.",
+        Text::Checklist => "Checklist (1-9 untuk mencentang)",
+        Text::NoChecklistItems => "Belum ada item checklist. Tekan u untuk menambah.",
+        Text::Settings => "Pengaturan",
+        Text::LastSevenDays => "7 Hari Terakhir (tekan s untuk kembali)",
+        Text::PerDay => "Per Hari",
+        Text::ByLanguage => "Per Bahasa",
+        Text::ByHour => "Per Jam",
+        Text::EnlargeTerminal => "Perbesar jendela terminal Anda",
+        Text::DailyGoal => "Target Harian",
+        Text::StatePaused => "Dijeda",
+        Text::StateIdle => "Diam",
+        Text::StateFocus => "Fokus",
+        Text::StateBreak => "Istirahat",
+        Text::StateStopwatch => "Stopwatch",
+        Text::LongBreak => "Istirahat panjang",
+        Text::QuickTimer => "Timer cepat",
+        Text::Left => "lagi",
+        Text::Elapsed => "berjalan",
+        Text::NextLongBreak => "Berikutnya: istirahat panjang",
+        Text::PausedResume => "dijeda, tekan p untuk melanjutkan",
+        Text::WrappingUp => "hampir selesai",
+        Text::Status => "Status",
+        Text::Completed => "Selesai",
+        Text::Priority => "Prioritas",
+        Text::FieldTask => "Tugas",
+        Text::FieldLanguage => "Bahasa",
+        Text::FieldEstimate => "Estimasi",
+        Text::EstimatePlaceholder => "pomodoro, opsional",
+        Text::TitleNewTask => "Tugas Baru (Tab/Shift+Tab untuk pindah kolom)",
+        Text::TitleMinutes => "Sesi Kustom (Mode Input Menit)",
+        Text::TitleChecklist => "Checklist (Mode Input Item)",
+        Text::TitleProject => "Proyek Baru (Mode Input Nama)",
+        Text::TitleDistraction => "Gangguan (Mode Input Catatan)",
+        Text::TitleSplit => "Pecah Tugas (Mode Input Nama)",
+        Text::TitleIntent => "Apa yang akan Anda selesaikan di sesi ini?",
+        Text::TitleImport => "Impor Tugas (Mode Input Path)",
+        Text::TitleCommand => "Palet Perintah",
+        Text::LabelMinutes => "Menit",
+        Text::LabelItem => "Item",
+        Text::LabelName => "Nama",
+        Text::LabelNote => "Catatan",
+        Text::LabelGoal => "Tujuan",
+        Text::LabelFile => "Berkas",
+        Text::HintForm => "Enter untuk menambah tugas, ESC untuk batal",
+        Text::HintMinutes => "Enter untuk memulai sesi ini saja, ESC untuk batal",
+        Text::HintChecklist => "Enter untuk menambah ke tugas terpilih, ESC untuk batal",
+        Text::HintProject => "Enter untuk membuat dan pindah ke proyek itu, ESC untuk batal",
+        Text::HintDistraction => {
+            "Enter untuk menyimpannya untuk nanti (timer tetap berjalan), ESC untuk batal"
+        }
+        Text::HintSplit => "Enter untuk menambahkannya setelah tugas terpilih, ESC untuk batal",
+        Text::HintIntent => "Enter untuk mulai (kosongkan untuk melewati), ESC untuk batal",
+        Text::HintImport => "Satu nama tugas per baris. Enter untuk mengimpor, ESC untuk batal",
+        Text::HintCommand => {
+            "mis. add, delete, stats, export, atau nama aksi apa pun. Enter untuk menjalankan"
+        }
+        Text::CompletedSessions => "Sesi fokus selesai",
+        Text::Added => "Ditambahkan",
+        Text::Interruptions => "Interupsi",
+        Text::ToFinish => "lagi sampai selesai",
+        Text::RecurringDaily => "Berulang harian",
+        Text::Lifetime => "Sepanjang masa",
+        Text::Pomodoros => "pomodoro",
+        Text::Focused => "fokus",
+        Text::LifetimeFocus => "Fokus sepanjang masa",
+        Text::FocusRatio => "Rasio fokus",
+        Text::FocusShare => "fokus",
+        Text::BreakShare => "istirahat",
+        Text::NoTimeTracked => "belum ada waktu tercatat",
+        Text::ColumnTasks => "Tugas",
+        Text::ColumnPomodoros => "Pomodoro",
+        Text::PeakHour => "Pomodoro paling banyak selesai pukul",
+        Text::NoPomodorosYet => "Belum ada pomodoro yang selesai",
+        Text::DistractionsTitle => "Gangguan (j=tambah  del=hapus semua  J=kembali)",
+        Text::NoDistractions => "Belum ada catatan. Tekan j saat sesi untuk mencatat pikiran.",
+        Text::ArchiveTitle => "Tugas Diarsipkan (↑/↓=pilih  enter=pulihkan  W=kembali)",
+        Text::NoArchived => "Belum ada arsip. Tekan a pada tugas untuk menyisihkannya.",
+        Text::AllKeys => "Semua Tombol (?=kembali)",
+    })
+}
+
+pub struct Settings {
+    pub alert_sound: AlertSound,
+    pub alert_volume: u8,
+    pub message_duration: Duration,
+    pub idle_timeout: Option<Duration>,
+    pub time_format: TimeFormat,
+    /// Show the countdown as whole minutes left, rounded up, instead of MM:SS.
+    pub hide_seconds: bool,
+    /// Show how much of the session has passed next to the time left.
+    pub show_elapsed: bool,
+    /// The gauge blinks for this long before a focus session ends.
+    pub wrap_up_warning: Option<Duration>,
+    /// Pause focus timers while the terminal window is in the background.
+    pub pause_on_focus_loss: bool,
+    pub locale: Locale,
+    pub theme: Theme,
+    /// Ask what each work session should accomplish before it starts.
+    pub ask_intent: bool,
+    /// Mark a task done once its completed pomodoros reach the estimate.
+    pub auto_complete: bool,
+    /// Add the elapsed part of a work session stopped early to focus time.
+    pub credit_partial: bool,
+    /// Quit once this many work sessions complete in one run.
+    pub auto_exit_after: Option<u32>,
+    /// Completed pomodoros between long breaks; at least 1.
+    pub long_break_every: u32,
+    /// Pomodoros to finish per day, shown as a second gauge.
+    pub daily_goal: Option<u32>,
+    /// Copies of the task list kept from before the latest saves; 0 is off.
+    pub backup_count: u32,
+    /// Append each completed work session to the session log.
+    pub log_sessions: bool,
+    /// How long the main loop waits for input before redrawing.
+    pub tick_interval: Duration,
+    /// Languages the `l` key cycles a task through.
+    pub languages: Vec<String>,
+    /// Shell commands run when a work session starts and when it finishes,
+    /// e.g. to start a music player or toggle do-not-disturb.
+    pub on_work_start: Option<String>,
+    pub on_work_end: Option<String>,
+    /// Replaces the built-in task list rows, e.g. "{name} [{language}]".
+    pub list_format: Option<String>,
+    /// Keys rebound from their defaults.
+    pub keybindings: Vec<(Action, char)>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            alert_sound: AlertSound::Chime,
+            alert_volume: 70,
+            message_duration: MESSAGE_VISIBLE_FOR,
+            idle_timeout: None,
+            time_format: TimeFormat::Auto,
+            hide_seconds: false,
+            show_elapsed: false,
+            wrap_up_warning: Some(Duration::from_secs(60)),
+            tick_interval: DEFAULT_TICK,
+            pause_on_focus_loss: false,
+            log_sessions: true,
+            auto_exit_after: None,
+            long_break_every: LONG_BREAK_EVERY,
+            daily_goal: Some(8),
+            backup_count: 0,
+            credit_partial: true,
+            auto_complete: false,
+            locale: Locale::English,
+            theme: Theme::Dark,
+            ask_intent: false,
+            languages: DEFAULT_LANGUAGES.iter().map(|l| l.to_string()).collect(),
+            on_work_start: None,
+            on_work_end: None,
+            list_format: None,
+            keybindings: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// One line per row of the settings screen.
+    pub fn rows(&self) -> Vec<String> {
+        vec![
+            format!("Alert sound: {}", self.alert_sound.label()),
+            format!("Volume: {}%", self.alert_volume),
+            format!("Message duration: {}s", self.message_duration.as_secs()),
+            match self.idle_timeout {
+                Some(timeout) => format!("Idle auto-pause: {} min", timeout.as_secs() / 60),
+                None => "Idle auto-pause: Off".to_string(),
+            },
+            format!("Time format: {}", self.time_format.label()),
+            match self.wrap_up_warning {
+                Some(warning) => format!("Wrap-up blink: last {}s", warning.as_secs()),
+                None => "Wrap-up blink: Off".to_string(),
+            },
+            format!("Refresh interval: {}ms", self.tick_interval.as_millis()),
+            format!(
+                "Pause when terminal loses focus: {}",
+                on_off(self.pause_on_focus_loss)
+            ),
+            format!(
+                "Session log ({}): {}",
+                SESSION_LOG_FILE,
+                on_off(self.log_sessions)
+            ),
+            match self.auto_exit_after {
+                Some(count) => format!("Quit after: {} pomodoros", count),
+                None => "Quit after: Off".to_string(),
+            },
+            format!("Credit partial sessions: {}", on_off(self.credit_partial)),
+            format!("Interface language: {}", self.locale.label()),
+            match self.daily_goal {
+                Some(goal) => format!("Daily goal: {} pomodoros", goal),
+                None => "Daily goal: Off".to_string(),
+            },
+            match self.backup_count {
+                0 => "Backups kept: Off".to_string(),
+                count => format!("Backups kept: {}", count),
+            },
+            format!("Background: {}", self.theme.label()),
+            format!("Ask for a session goal: {}", on_off(self.ask_intent)),
+            format!("Long break every: {} pomodoros", self.long_break_every),
+            format!("Hide countdown seconds: {}", on_off(self.hide_seconds)),
+            format!("Mark done at estimate: {}", on_off(self.auto_complete)),
+            format!("Show elapsed time: {}", on_off(self.show_elapsed)),
+        ]
+    }
+
+    /// Formats the time left in a session, honouring `hide_seconds`.
+    pub fn format_countdown(&self, remaining: Duration, span: Duration) -> String {
+        if self.hide_seconds {
+            format!("{} min", remaining.as_secs().div_ceil(60))
+        } else {
+            self.time_format.format(remaining, span)
+        }
+    }
+
+    /// Like `format_countdown`, but rounds whole minutes down so elapsed and
+    /// remaining add up to the session length.
+    pub fn format_elapsed(&self, elapsed: Duration, span: Duration) -> String {
+        if self.hide_seconds {
+            format!("{} min", elapsed.as_secs() / 60)
+        } else {
+            self.time_format.format(elapsed, span)
+        }
+    }
+
+    pub fn adjust(&mut self, row: usize, forward: bool) {
+        match row {
+            0 => self.alert_sound = self.alert_sound.cycle(forward),
+            1 => {
+                self.alert_volume = if forward {
+                    (self.alert_volume + VOLUME_STEP).min(100)
+                } else {
+                    self.alert_volume.saturating_sub(VOLUME_STEP)
+                };
+            }
+            2 => {
+                let secs = self.message_duration.as_secs();
+                let secs = if forward {
+                    secs + 1
+                } else {
+                    secs.saturating_sub(1)
+                };
+                self.message_duration = Duration::from_secs(secs.clamp(1, MAX_MESSAGE_SECS));
+            }
+            3 => {
+                let minutes = self.idle_timeout.map_or(0, |t| t.as_secs() / 60);
+                let index = IDLE_TIMEOUT_CHOICES
+                    .iter()
+                    .position(|&m| m == minutes)
+                    .unwrap_or(0);
+                let len = IDLE_TIMEOUT_CHOICES.len();
+                let next = if forward { index + 1 } else { index + len - 1 };
+                self.idle_timeout = idle_timeout_from_minutes(IDLE_TIMEOUT_CHOICES[next % len]);
+            }
+            4 => self.time_format = self.time_format.cycle(forward),
+            5 => {
+                let secs = self.wrap_up_warning.map_or(0, |w| w.as_secs());
+                let index = WRAP_UP_CHOICES.iter().position(|&s| s == secs).unwrap_or(0);
+                let len = WRAP_UP_CHOICES.len();
+                let next = if forward { index + 1 } else { index + len - 1 };
+                self.wrap_up_warning = wrap_up_from_secs(WRAP_UP_CHOICES[next % len]);
+            }
+            6 => {
+                let millis = self.tick_interval.as_millis() as u64;
+                let millis = if forward {
+                    millis + TICK_STEP_MS
+                } else {
+                    millis.saturating_sub(TICK_STEP_MS)
+                };
+                self.tick_interval = tick_from_millis(millis);
+            }
+            7 => self.pause_on_focus_loss = !self.pause_on_focus_loss,
+            8 => self.log_sessions = !self.log_sessions,
+            9 => {
+                let count = self.auto_exit_after.unwrap_or(0);
+                let index = AUTO_EXIT_CHOICES
+                    .iter()
+                    .position(|&c| c == count)
+                    .unwrap_or(0);
+                let len = AUTO_EXIT_CHOICES.len();
+                let next = if forward { index + 1 } else { index + len - 1 };
+                self.auto_exit_after = Some(AUTO_EXIT_CHOICES[next % len]).filter(|&c| c > 0);
+            }
+            10 => self.credit_partial = !self.credit_partial,
+            11 => self.locale = self.locale.cycle(forward),
+            12 => {
+                let goal = self.daily_goal.unwrap_or(0);
+                let index = DAILY_GOAL_CHOICES
+                    .iter()
+                    .position(|&g| g == goal)
+                    .unwrap_or(0);
+                let len = DAILY_GOAL_CHOICES.len();
+                let next = if forward { index + 1 } else { index + len - 1 };
+                self.daily_goal = Some(DAILY_GOAL_CHOICES[next % len]).filter(|&g| g > 0);
+            }
+            13 => {
+                let index = BACKUP_CHOICES
+                    .iter()
+                    .position(|&c| c == self.backup_count)
+                    .unwrap_or(0);
+                let len = BACKUP_CHOICES.len();
+                let next = if forward { index + 1 } else { index + len - 1 };
+                self.backup_count = BACKUP_CHOICES[next % len];
+            }
+            14 => self.theme = self.theme.cycle(forward),
+            15 => self.ask_intent = !self.ask_intent,
+            16 => {
+                let every = if forward {
+                    self.long_break_every + 1
+                } else {
+                    self.long_break_every.saturating_sub(1)
+                };
+                self.long_break_every = every.clamp(1, MAX_LONG_BREAK_EVERY);
+            }
+            17 => self.hide_seconds = !self.hide_seconds,
+            18 => self.auto_complete = !self.auto_complete,
+            19 => self.show_elapsed = !self.show_elapsed,
+            _ => {}
+        }
+    }
+}
+
+const MESSAGE_VISIBLE_FOR: Duration = Duration::from_secs(4);
+const KEYBINDING_PREFIX: &str = "key_";
+const MAX_MESSAGE_SECS: u64 = 60;
+/// Redraws ten times a second: smooth enough for the timers and input without
+/// noticeable CPU use. Slower ticks save CPU but make keys feel laggy.
+const DEFAULT_TICK: Duration = Duration::from_millis(100);
+const TICK_STEP_MS: u64 = 50;
+const MIN_TICK_MS: u64 = 50;
+const MAX_TICK_MS: u64 = 1000;
+/// Idle auto-pause choices in minutes; 0 turns the feature off.
+const IDLE_TIMEOUT_CHOICES: [u64; 5] = [0, 5, 10, 15, 30];
+const DEFAULT_LANGUAGES: [&str; 5] = ["Rust", "Python", "Go", "TypeScript", "C++"];
+const AUTO_EXIT_CHOICES: [u32; 5] = [0, 2, 4, 6, 8];
+const DAILY_GOAL_CHOICES: [u32; 6] = [0, 4, 6, 8, 10, 12];
+const BACKUP_CHOICES: [u32; 4] = [0, 3, 5, 10];
+const WRAP_UP_CHOICES: [u64; 5] = [0, 30, 60, 120, 300];
+const VOLUME_STEP: u8 = 10;
+const MAX_LONG_BREAK_EVERY: u32 = 12;
+
+pub fn load_settings() -> Settings {
+    let mut settings = Settings::default();
+    if let Ok(content) = fs::read_to_string("settings.txt") {
+        for line in content.lines() {
+            match line.split_once(" = ") {
+                Some(("alert_sound", v)) => {
+                    if let Some(sound) = AlertSound::from_key(v) {
+                        settings.alert_sound = sound;
+                    }
+                }
+                Some(("alert_volume", v)) => {
+                    if let Ok(volume) = v.parse::<u8>() {
+                        settings.alert_volume = volume.min(100);
+                    }
+                }
+                Some(("idle_pause_minutes", v)) => {
+                    if let Ok(minutes) = v.parse::<u64>() {
+                        settings.idle_timeout = idle_timeout_from_minutes(minutes);
+                    }
+                }
+                Some(("time_format", v)) => {
+                    if let Some(format) = TimeFormat::from_key(v) {
+                        settings.time_format = format;
+                    }
+                }
+                Some(("wrap_up_seconds", v)) => {
+                    if let Ok(secs) = v.parse::<u64>() {
+                        settings.wrap_up_warning = wrap_up_from_secs(secs);
+                    }
+                }
+                Some(("pause_on_focus_loss", v)) => settings.pause_on_focus_loss = v == "true",
+                Some(("backup_count", v)) => {
+                    if let Ok(count) = v.parse::<u32>() {
+                        settings.backup_count = count;
+                    }
+                }
+                Some(("long_break_every", v)) => {
+                    if let Ok(every) = v.parse::<u32>() {
+                        settings.long_break_every = every.clamp(1, MAX_LONG_BREAK_EVERY);
+                    }
+                }
+                Some(("daily_goal", v)) => {
+                    if let Ok(goal) = v.parse::<u32>() {
+                        settings.daily_goal = Some(goal).filter(|&g| g > 0);
+                    }
+                }
+                Some(("auto_exit_after", v)) => {
+                    settings.auto_exit_after = v.parse::<u32>().ok().filter(|&c| c > 0);
+                }
+                Some(("locale", v)) => {
+                    if let Some(locale) = Locale::from_key(v) {
+                        settings.locale = locale;
+                    }
+                }
+                Some(("theme", v)) => {
+                    if let Some(theme) = Theme::from_key(v) {
+                        settings.theme = theme;
+                    }
+                }
+                Some(("credit_partial", v)) => settings.credit_partial = v == "true",
+                Some(("auto_complete", v)) => settings.auto_complete = v == "true",
+                Some(("ask_intent", v)) => settings.ask_intent = v == "true",
+                Some(("hide_seconds", v)) => settings.hide_seconds = v == "true",
+                Some(("show_elapsed", v)) => settings.show_elapsed = v == "true",
+                Some(("log_sessions", v)) => settings.log_sessions = v == "true",
+                Some(("tick_millis", v)) => {
+                    if let Ok(millis) = v.parse::<u64>() {
+                        settings.tick_interval = tick_from_millis(millis);
+                    }
+                }
+                Some(("languages", v)) => {
+                    settings.languages = v
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|l| !l.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                Some(("on_work_start", v)) if !v.trim().is_empty() => {
+                    settings.on_work_start = Some(v.trim().to_string());
+                }
+                Some(("on_work_end", v)) if !v.trim().is_empty() => {
+                    settings.on_work_end = Some(v.trim().to_string());
+                }
+                Some(("list_format", v)) if !v.trim().is_empty() => {
+                    settings.list_format = Some(v.trim().to_string());
+                }
+                Some((name, v)) if name.starts_with(KEYBINDING_PREFIX) => {
+                    let mut chars = v.trim().chars();
+                    if let (Some(action), Some(key), None) = (
+                        Action::from_name(&name[KEYBINDING_PREFIX.len()..]),
+                        chars.next(),
+                        chars.next(),
+                    ) {
+                        settings.keybindings.retain(|(bound, _)| *bound != action);
+                        settings.keybindings.push((action, key));
+                    }
+                }
+                Some(("message_seconds", v)) => {
+                    if let Ok(secs) = v.parse::<u64>() {
+                        settings.message_duration =
+                            Duration::from_secs(secs.clamp(1, MAX_MESSAGE_SECS));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    settings
+}
+
+fn idle_timeout_from_minutes(minutes: u64) -> Option<Duration> {
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+fn wrap_up_from_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+fn tick_from_millis(millis: u64) -> Duration {
+    Duration::from_millis(millis.clamp(MIN_TICK_MS, MAX_TICK_MS))
+}
+
+pub fn save_settings(settings: &Settings) {
+    let mut content = format!(
+        "alert_sound = {}\nalert_volume = {}\nmessage_seconds = {}\nidle_pause_minutes = {}\ntime_format = {}\nwrap_up_seconds = {}\ntick_millis = {}\npause_on_focus_loss = {}\nlog_sessions = {}\nauto_exit_after = {}\nlong_break_every = {}\ndaily_goal = {}\nbackup_count = {}\ncredit_partial = {}\nauto_complete = {}\nlocale = {}\ntheme = {}\nask_intent = {}\nhide_seconds = {}\nshow_elapsed = {}\nlanguages = {}\n",
+        settings.alert_sound.key(),
+        settings.alert_volume,
+        settings.message_duration.as_secs(),
+        settings.idle_timeout.map_or(0, |t| t.as_secs() / 60),
+        settings.time_format.key(),
+        settings.wrap_up_warning.map_or(0, |w| w.as_secs()),
+        settings.tick_interval.as_millis(),
+        settings.pause_on_focus_loss,
+        settings.log_sessions,
+        settings.auto_exit_after.unwrap_or(0),
+        settings.long_break_every,
+        settings.daily_goal.unwrap_or(0),
+        settings.backup_count,
+        settings.credit_partial,
+        settings.auto_complete,
+        settings.locale.key(),
+        settings.theme.key(),
+        settings.ask_intent,
+        settings.hide_seconds,
+        settings.show_elapsed,
+        settings.languages.join(", ")
+    );
+    // Hooks are only edited by hand, but must survive saves from the settings screen.
+    if let Some(command) = &settings.on_work_start {
+        content.push_str(&format!("on_work_start = {}\n", command));
+    }
+    if let Some(command) = &settings.on_work_end {
+        content.push_str(&format!("on_work_end = {}\n", command));
+    }
+    if let Some(template) = &settings.list_format {
+        content.push_str(&format!("list_format = {}\n", template));
+    }
+    for (action, key) in &settings.keybindings {
+        content.push_str(&format!(
+            "{}{} = {}\n",
+            KEYBINDING_PREFIX,
+            action.name(),
+            key
+        ));
+    }
+    let _ = fs::write("settings.txt", content);
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
@@ -0,0 +1,103 @@
+//! Alert tones.
+
+use crate::settings::{AlertSound, Settings};
+#[cfg(target_os = "macos")]
+use std::fs;
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+const SAMPLE_RATE: u32 = 22_050;
+
+/// Plays the configured alert without blocking the UI. Missing audio
+/// players or devices are ignored.
+pub fn play_alert(settings: &Settings) {
+    match settings.alert_sound {
+        AlertSound::Off => {}
+        AlertSound::Bell => {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07");
+            let _ = stdout.flush();
+        }
+        sound => {
+            if settings.alert_volume == 0 {
+                return;
+            }
+            let wav = synth_wav(sound.notes(), settings.alert_volume);
+            thread::spawn(move || {
+                let _ = play_wav(&wav);
+            });
+        }
+    }
+}
+
+/// Renders a sequence of sine tones as a 16-bit mono WAV file.
+fn synth_wav(notes: &[(f32, u32)], volume: u8) -> Vec<u8> {
+    let amplitude = f32::from(i16::MAX) * 0.8 * f32::from(volume) / 100.0;
+    let mut samples: Vec<i16> = Vec::new();
+    for &(frequency, millis) in notes {
+        let count = SAMPLE_RATE * millis / 1000;
+        for n in 0..count {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            // Short linear fade at both ends avoids audible clicks.
+            let fade = (n.min(count - n) as f32 / (SAMPLE_RATE as f32 * 0.01)).min(1.0);
+            let value = (2.0 * std::f32::consts::PI * frequency * t).sin() * amplitude * fade;
+            samples.push(value as i16);
+        }
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(target_os = "macos")]
+fn play_wav(wav: &[u8]) -> io::Result<()> {
+    let path = std::env::temp_dir().join("todo-tui-alert.wav");
+    fs::write(&path, wav)?;
+    Command::new("afplay")
+        .arg(&path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn play_wav(wav: &[u8]) -> io::Result<()> {
+    for (player, args) in [("paplay", &[][..]), ("aplay", &["-q"][..])] {
+        let Ok(mut child) = Command::new(player)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(wav)?;
+        }
+        if child.wait()?.success() {
+            break;
+        }
+    }
+    Ok(())
+}
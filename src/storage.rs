@@ -0,0 +1,581 @@
+//! Reading and writing the task files, archives, logs and exports.
+
+use crate::{
+    format::{
+        field_value, format_day, format_hours_minutes, format_local_time, local_day, unix_secs,
+    },
+    task::{PomodoroState, Priority, Project, Session, Task, LABELS},
+};
+use std::{
+    cmp::Reverse,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const TODO_FILE: &str = "todo_list.txt";
+const ARCHIVE_FILE: &str = "archived_list.txt";
+/// Appended to a list's stem to name its archive, e.g. `side.archived.txt`.
+const ARCHIVE_SUFFIX: &str = ".archived.txt";
+/// Extra projects are stored as `<name>.txt` in this directory next to the
+/// main list.
+pub const PROJECTS_DIR: &str = "projects";
+pub const SESSION_LOG_FILE: &str = "sessions.log";
+pub const SUMMARY_PREFIX: &str = "summary-";
+pub const DISTRACTIONS_FILE: &str = "distractions.txt";
+/// Format version written as the first line of task files.
+const DATA_VERSION: u32 = 2;
+const VERSION_PREFIX: &str = "# version=";
+pub const BACKUPS_DIR: &str = "backups";
+pub const DATA_FILE_ENV: &str = "POMODORO_FILE";
+/// Language given to tasks imported from a plain list of names.
+pub const IMPORTED_LANGUAGE: &str = "Unknown";
+
+pub fn load_distractions(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads a plain text file with one task name per line, e.g. exported from
+/// a notes app. Blank lines are skipped.
+pub fn import_plain(path: &str) -> io::Result<Vec<Task>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(field_value)
+        .filter(|name| !name.is_empty())
+        .map(|name| Task::new(name, IMPORTED_LANGUAGE.to_string()))
+        .collect())
+}
+
+/// Copies `path` to `backups/<file name>.<unix time>` and deletes all but
+/// the newest `keep` copies of it.
+fn rotate_backups(path: &str, keep: u32) -> io::Result<()> {
+    let path = Path::new(path);
+    let dir = path.with_file_name(BACKUPS_DIR);
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    fs::create_dir_all(&dir)?;
+    let prefix = format!("{}.", name);
+    fs::copy(
+        path,
+        dir.join(format!("{}{}", prefix, unix_secs(SystemTime::now()))),
+    )?;
+
+    let mut backups: Vec<(u64, PathBuf)> = fs::read_dir(&dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let stamp = file_name.strip_prefix(&prefix)?.parse::<u64>().ok()?;
+            Some((stamp, entry.path()))
+        })
+        .collect();
+    backups.sort_by_key(|(stamp, _)| Reverse(*stamp));
+    for (_, old) in backups.into_iter().skip(keep as usize) {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Archives live next to the list they came from, one per list. The default
+/// list keeps the original `archived_list.txt`.
+pub fn archive_path_for(data_path: &str) -> String {
+    let path = Path::new(data_path);
+    let name = match path.file_stem() {
+        Some(stem) if path.file_name().is_some_and(|name| name != TODO_FILE) => {
+            format!("{}{}", stem.to_string_lossy(), ARCHIVE_SUFFIX)
+        }
+        _ => ARCHIVE_FILE.to_string(),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Whether `file_name` is an archive rather than a task list.
+pub fn is_archive_file(file_name: &str) -> bool {
+    file_name == ARCHIVE_FILE || file_name.ends_with(ARCHIVE_SUFFIX)
+}
+
+/// The main list plus every `.txt` list in the projects directory next to it,
+/// sorted by name.
+pub fn find_projects(data_path: &str) -> Vec<Project> {
+    let main_name = Path::new(data_path)
+        .file_stem()
+        .map_or_else(|| "main".to_string(), |s| s.to_string_lossy().into_owned());
+    let mut extra: Vec<Project> = fs::read_dir(Path::new(data_path).with_file_name(PROJECTS_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "txt")
+                && path
+                    .file_name()
+                    .is_some_and(|name| !is_archive_file(&name.to_string_lossy()))
+        })
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some(Project {
+                name,
+                path: path.to_string_lossy().into_owned(),
+            })
+        })
+        .collect();
+    extra.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut projects = vec![Project {
+        name: main_name,
+        path: data_path.to_string(),
+    }];
+    projects.extend(extra);
+    projects
+}
+
+/// Reads a task file. Older versions are upgraded while loading, so the
+/// next save writes them back in the current format.
+/// `long_break_every` is needed to fast-forward timers that kept running
+/// while the app was closed, and `now`, the app clock's current instant, to
+/// restart them.
+pub fn load_todos(path: &str, long_break_every: u32, now: Instant) -> Vec<Task> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let mut lines = content.lines().peekable();
+            // Files from before the header existed are version 1.
+            let version = match lines.peek().and_then(|l| l.strip_prefix(VERSION_PREFIX)) {
+                Some(v) => {
+                    lines.next();
+                    v.trim().parse::<u32>().unwrap_or(DATA_VERSION)
+                }
+                None => 1,
+            };
+            lines
+                .map(|s| {
+                    let parts: Vec<&str> = s.split(" | ").collect();
+                    let completed = parts
+                        .get(2)
+                        .and_then(|v| v.parse::<u32>().ok())
+                        .unwrap_or(0);
+                    let mut task = Task::new(
+                        parts[0].to_string(),
+                        parts.get(1).unwrap_or(&"Unknown").to_string(),
+                    );
+                    task.completed_pomodoros = completed;
+
+                    let mut state = None;
+                    let mut started = None;
+                    let mut focus = None;
+                    let mut paused = None;
+                    for field in parts.iter().skip(3) {
+                        match field.split_once('=') {
+                            Some(("state", v)) => state = Some(v),
+                            Some(("started", v)) => started = v.parse::<u64>().ok(),
+                            Some(("paused", v)) => paused = v.parse::<u64>().ok(),
+                            Some(("history", v)) => task.history = parse_history(v),
+                            Some(("checklist", v)) => task.checklist = parse_checklist(v),
+                            Some(("priority", v)) => {
+                                task.priority = Priority::parse(v).unwrap_or(Priority::Normal)
+                            }
+                            Some(("estimate", v)) => {
+                                task.estimated_pomodoros = v.parse::<u32>().ok()
+                            }
+                            Some(("pinned", "1")) => task.pinned = true,
+                            Some(("done", "1")) => task.done = true,
+                            Some(("recurring", "1")) => task.recurring = true,
+                            Some(("reset", v)) => {
+                                task.reset_at = v
+                                    .parse::<u64>()
+                                    .ok()
+                                    .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+                            }
+                            Some(("lifetime", v)) => {
+                                task.lifetime_pomodoros = v.parse().unwrap_or(0)
+                            }
+                            Some(("breaks", v)) => {
+                                task.total_break_time = Duration::from_secs(v.parse().unwrap_or(0))
+                            }
+                            Some(("interruptions", v)) => {
+                                task.interruptions = v.parse().unwrap_or(0)
+                            }
+                            Some(("label", v)) => task.label = LABELS.into_iter().find(|l| *l == v),
+                            Some(("intent", v)) => task.intent = Some(v.to_string()),
+                            Some(("created", v)) => {
+                                if let Ok(secs) = v.parse::<u64>() {
+                                    task.created_at = UNIX_EPOCH + Duration::from_secs(secs);
+                                }
+                            }
+                            Some(("last_worked", v)) => {
+                                task.last_worked = v
+                                    .parse::<u64>()
+                                    .ok()
+                                    .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+                            }
+                            Some(("focus", v)) => {
+                                focus = v.parse::<u64>().ok().map(Duration::from_secs)
+                            }
+                            Some(("session", v)) => {
+                                task.session_duration =
+                                    v.parse::<u64>().ok().map(Duration::from_secs)
+                            }
+                            _ => {}
+                        }
+                    }
+                    // Version 1 files may predate focus tracking, so fall back to
+                    // the recorded session history.
+                    task.total_focus_time = match focus {
+                        Some(focus) => focus,
+                        None if version < 2 => task.history.iter().map(|s| s.duration).sum(),
+                        None => Duration::ZERO,
+                    };
+                    if let (Some(state), Some(paused)) = (state, paused) {
+                        restore_paused(&mut task, state, Duration::from_secs(paused));
+                    } else if let (Some(state), Some(started)) = (state, started) {
+                        let started = UNIX_EPOCH + Duration::from_secs(started);
+                        restore_timer(&mut task, state, started, long_break_every, now);
+                    }
+                    task
+                })
+                .collect()
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Rebuilds a timer that was running when the app last quit. Phases that
+/// would already have finished in the meantime are fast-forwarded.
+fn restore_timer(
+    task: &mut Task,
+    state: &str,
+    started: SystemTime,
+    long_break_every: u32,
+    now: Instant,
+) {
+    let mut elapsed = SystemTime::now()
+        .duration_since(started)
+        .unwrap_or_default();
+
+    if state == "stopwatch" {
+        resume_phase(task, PomodoroState::Stopwatch, elapsed, now);
+        return;
+    }
+
+    if state == "work" {
+        if elapsed < task.work_duration() {
+            resume_phase(task, PomodoroState::Work, elapsed, now);
+            return;
+        }
+        elapsed -= task.work_duration();
+        task.history.push(Session {
+            finished_at: started + task.work_duration(),
+            duration: task.work_duration(),
+        });
+        task.total_focus_time += task.work_duration();
+        task.completed_pomodoros += 1;
+        task.session_duration = None;
+    } else if state != "break" {
+        return;
+    }
+
+    let break_duration = task.break_duration(long_break_every);
+    if elapsed < break_duration {
+        resume_phase(task, PomodoroState::Break, elapsed, now);
+    } else {
+        task.total_break_time += break_duration;
+        task.session_duration = None;
+    }
+}
+
+/// A paused phase is restored exactly as it was left.
+fn restore_paused(task: &mut Task, state: &str, elapsed: Duration) {
+    task.pomodoro_state = match state {
+        "work" => PomodoroState::Work,
+        "break" => PomodoroState::Break,
+        "stopwatch" => PomodoroState::Stopwatch,
+        _ => return,
+    };
+    task.paused = Some(elapsed);
+}
+
+fn parse_history(value: &str) -> Vec<Session> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (finished, duration) = entry.split_once(':')?;
+            Some(Session {
+                finished_at: UNIX_EPOCH + Duration::from_secs(finished.parse().ok()?),
+                duration: Duration::from_secs(duration.parse().ok()?),
+            })
+        })
+        .collect()
+}
+
+/// Items are stored as `x:text` (done) or `o:text` (open), separated by `;`.
+fn parse_checklist(value: &str) -> Vec<(String, bool)> {
+    value
+        .split(';')
+        .filter_map(|entry| {
+            let (mark, item) = entry.split_once(':')?;
+            Some((item.to_string(), mark == "x"))
+        })
+        .collect()
+}
+
+fn resume_phase(task: &mut Task, state: PomodoroState, elapsed: Duration, now: Instant) {
+    if let Some(start) = now.checked_sub(elapsed) {
+        task.pomodoro_state = state;
+        task.pomodoro_start = Some(start);
+    }
+}
+
+/// When the file at `path` was last written, if it can be told.
+pub fn modified_at(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Writes a task file. With `keep_backups` above zero the previous version
+/// is first copied into the backups directory next to it. `now` is the
+/// app clock's current instant, for turning running timers into start times.
+pub fn save_todos(path: &str, todos: &[Task], keep_backups: u32, now: Instant) -> io::Result<()> {
+    if keep_backups > 0 && Path::new(path).exists() {
+        // A failed backup must not keep the list itself from being saved.
+        let _ = rotate_backups(path, keep_backups);
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    writeln!(file, "{}{}", VERSION_PREFIX, DATA_VERSION)?;
+    for task in todos {
+        let mut line = format!(
+            "{} | {} | {}",
+            task.name, task.language, task.completed_pomodoros
+        );
+
+        if task.priority != Priority::Normal {
+            line.push_str(&format!(" | priority={}", task.priority.key()));
+        }
+        if let Some(estimate) = task.estimated_pomodoros {
+            line.push_str(&format!(" | estimate={}", estimate));
+        }
+        if !task.checklist.is_empty() {
+            let items: Vec<String> = task
+                .checklist
+                .iter()
+                .map(|(item, done)| format!("{}:{}", if *done { "x" } else { "o" }, item))
+                .collect();
+            line.push_str(&format!(" | checklist={}", items.join(";")));
+        }
+        line.push_str(&format!(" | created={}", unix_secs(task.created_at)));
+        if task.pinned {
+            line.push_str(" | pinned=1");
+        }
+        if task.done {
+            line.push_str(" | done=1");
+        }
+        if task.recurring {
+            line.push_str(" | recurring=1");
+            if let Some(reset_at) = task.reset_at {
+                line.push_str(&format!(" | reset={}", unix_secs(reset_at)));
+            }
+        }
+        if task.lifetime_pomodoros > 0 {
+            line.push_str(&format!(" | lifetime={}", task.lifetime_pomodoros));
+        }
+        if let Some(label) = task.label {
+            line.push_str(&format!(" | label={}", label));
+        }
+        if !task.total_break_time.is_zero() {
+            line.push_str(&format!(" | breaks={}", task.total_break_time.as_secs()));
+        }
+        if task.interruptions > 0 {
+            line.push_str(&format!(" | interruptions={}", task.interruptions));
+        }
+        if let Some(last_worked) = task.last_worked {
+            line.push_str(&format!(" | last_worked={}", unix_secs(last_worked)));
+        }
+        if !task.total_focus_time.is_zero() {
+            line.push_str(&format!(" | focus={}", task.total_focus_time.as_secs()));
+        }
+
+        let state = match task.pomodoro_state {
+            PomodoroState::Work => Some("work"),
+            PomodoroState::Break => Some("break"),
+            PomodoroState::Stopwatch => Some("stopwatch"),
+            PomodoroState::Idle => None,
+        };
+        if let (Some(state), Some(paused)) = (state, task.paused) {
+            line.push_str(&format!(" | state={} | paused={}", state, paused.as_secs()));
+            if let Some(intent) = &task.intent {
+                line.push_str(&format!(" | intent={}", intent));
+            }
+            if let Some(session) = task.session_duration {
+                line.push_str(&format!(" | session={}", session.as_secs()));
+            }
+        } else if let (Some(state), Some(start)) = (state, task.pomodoro_start) {
+            let started = SystemTime::now()
+                .checked_sub(now.saturating_duration_since(start))
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            line.push_str(&format!(" | state={} | started={}", state, started));
+            if let Some(intent) = &task.intent {
+                line.push_str(&format!(" | intent={}", intent));
+            }
+            if let Some(session) = task.session_duration {
+                line.push_str(&format!(" | session={}", session.as_secs()));
+            }
+        }
+
+        if !task.history.is_empty() {
+            let history: Vec<String> = task
+                .history
+                .iter()
+                .map(|session| {
+                    format!(
+                        "{}:{}",
+                        unix_secs(session.finished_at),
+                        session.duration.as_secs()
+                    )
+                })
+                .collect();
+            line.push_str(&format!(" | history={}", history.join(",")));
+        }
+
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Writes a Markdown report of the work sessions finished on `day` and
+/// returns how many tasks appear in it.
+pub fn export_markdown(path: &str, todos: &[Task], day: i64) -> io::Result<usize> {
+    let mut out = format!("# Pomodoro summary — {}\n\n", format_day(day));
+    out.push_str("| Task | Language | Pomodoros | Focus time |\n");
+    out.push_str("| --- | --- | ---: | ---: |\n");
+
+    let mut count = 0;
+    let mut total_pomodoros = 0;
+    let mut total_focus = Duration::ZERO;
+    for task in todos {
+        let sessions: Vec<&Session> = task
+            .history
+            .iter()
+            .filter(|session| local_day(session.finished_at) == day)
+            .collect();
+        if sessions.is_empty() {
+            continue;
+        }
+        let focus: Duration = sessions.iter().map(|session| session.duration).sum();
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            task.name.replace('|', "\\|"),
+            task.language.replace('|', "\\|"),
+            sessions.len(),
+            format_hours_minutes(focus)
+        ));
+        count += 1;
+        total_pomodoros += sessions.len();
+        total_focus += focus;
+    }
+    out.push_str(&format!(
+        "| **Total** | | **{}** | **{}** |\n",
+        total_pomodoros,
+        format_hours_minutes(total_focus)
+    ));
+
+    fs::write(path, out)?;
+    Ok(count)
+}
+
+/// Appends one completed session to the human-readable log.
+pub fn log_session(path: &str, task: &Task, duration: Duration) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut line = format!(
+        "{} | {} | {} | {} min",
+        format_local_time(SystemTime::now()),
+        task.name,
+        task.language,
+        duration.as_secs() / 60
+    );
+    if let Some(intent) = &task.intent {
+        line.push_str(&format!(" | {}", intent));
+    }
+    writeln!(file, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn backups_keep_only_the_newest() {
+        let dir = env::temp_dir().join(format!("todo-tui-backups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(BACKUPS_DIR)).unwrap();
+        let path = dir.join(TODO_FILE);
+        fs::write(&path, "current").unwrap();
+        for stamp in 1..=4 {
+            let old = dir
+                .join(BACKUPS_DIR)
+                .join(format!("{}.{}", TODO_FILE, stamp));
+            fs::write(old, "old").unwrap();
+        }
+
+        rotate_backups(&path.to_string_lossy(), 2).unwrap();
+        let mut left: Vec<String> = fs::read_dir(dir.join(BACKUPS_DIR))
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left.len(), 2);
+        assert!(left.contains(&format!("{}.4", TODO_FILE)));
+        assert!(!left.contains(&format!("{}.3", TODO_FILE)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn each_project_gets_its_own_archive() {
+        let dir = env::temp_dir().join(format!("todo-tui-archives-{}", std::process::id()));
+        let projects_dir = dir.join(PROJECTS_DIR);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&projects_dir).unwrap();
+        let main = dir.join(TODO_FILE).to_string_lossy().into_owned();
+        let side = projects_dir.join("side.txt").to_string_lossy().into_owned();
+        let other = projects_dir
+            .join("other.txt")
+            .to_string_lossy()
+            .into_owned();
+        for path in [&side, &other] {
+            save_todos(path, &[], 0, Instant::now()).unwrap();
+            save_todos(&archive_path_for(path), &[], 0, Instant::now()).unwrap();
+        }
+
+        assert!(archive_path_for(&main).ends_with(ARCHIVE_FILE));
+        assert!(archive_path_for(&side).ends_with("side.archived.txt"));
+        assert_ne!(archive_path_for(&side), archive_path_for(&other));
+        let names: Vec<String> = find_projects(&main).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["todo_list", "other", "side"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plain_import_skips_blank_lines() {
+        let path = env::temp_dir().join(format!("todo-tui-import-{}.txt", std::process::id()));
+        fs::write(&path, "Write docs\n\n   \n  Fix   the build \n").unwrap();
+        let tasks = import_plain(&path.to_string_lossy()).unwrap();
+        let names: Vec<&str> = tasks.iter().map(|task| task.name.as_str()).collect();
+        assert_eq!(names, ["Write docs", "Fix the build"]);
+        assert!(tasks.iter().all(|task| task.language == IMPORTED_LANGUAGE));
+        let _ = fs::remove_file(&path);
+    }
+}
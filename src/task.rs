@@ -0,0 +1,288 @@
+//! A task and its pomodoro timer.
+
+use crate::format::{local_day, local_hour};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    pub fn label(self) -> &'static str {
+        match self {
+            Priority::Low => "Low",
+            Priority::Normal => "Normal",
+            Priority::High => "High",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+        }
+    }
+
+    /// Accepts full names or first letters; blank means Normal.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "h" | "high" => Some(Priority::High),
+            "" | "n" | "normal" | "m" | "medium" => Some(Priority::Normal),
+            "l" | "low" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PomodoroState {
+    Idle,
+    Work,
+    Break,
+    /// Counts focus time up with no fixed length or automatic break.
+    Stopwatch,
+}
+
+pub const WORK_DURATION: Duration = Duration::from_secs(25 * 60);
+pub const BREAK_DURATION: Duration = Duration::from_secs(5 * 60);
+pub const LONG_BREAK_DURATION: Duration = Duration::from_secs(15 * 60);
+/// Every this many completed pomodoros earns a long break, unless changed
+/// in settings.
+pub const LONG_BREAK_EVERY: u32 = 4;
+pub const EXTENSION: Duration = Duration::from_secs(5 * 60);
+pub const MAX_EXTENSIONS: u32 = 3;
+pub const LABELS: [&str; 6] = ["🔴", "🟠", "🟡", "🟢", "🔵", "🟣"];
+
+pub struct Task {
+    pub name: String,
+    pub language: String,
+    pub pomodoro_state: PomodoroState,
+    pub pomodoro_start: Option<Instant>,
+    pub completed_pomodoros: u32,
+    pub session_duration: Option<Duration>,
+    pub history: Vec<Session>,
+    pub total_focus_time: Duration,
+    pub last_worked: Option<SystemTime>,
+    pub checklist: Vec<(String, bool)>,
+    /// Elapsed time of a paused phase; `pomodoro_start` is `None` meanwhile.
+    pub paused: Option<Duration>,
+    pub priority: Priority,
+    pub estimated_pomodoros: Option<u32>,
+    pub created_at: SystemTime,
+    /// Pinned tasks are kept above the rest of the list.
+    pub pinned: bool,
+    pub done: bool,
+    /// Recurring tasks start each day with a fresh count and done flag.
+    pub recurring: bool,
+    /// When a recurring task was last reset; `created_at` until the first reset.
+    pub reset_at: Option<SystemTime>,
+    /// Pomodoros counted before the last daily reset or reopen.
+    pub lifetime_pomodoros: u32,
+    /// One of `LABELS`, shown before the name.
+    pub label: Option<&'static str>,
+    /// Interruptions logged during this task's work sessions.
+    pub interruptions: u32,
+    /// Time spent in breaks that ran to completion.
+    pub total_break_time: Duration,
+    /// Times the current work session has been extended with `>`.
+    pub extensions: u32,
+    /// What the current work session is meant to get done.
+    pub intent: Option<String>,
+}
+
+/// A named task list with its own data file.
+pub struct Project {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Default)]
+pub struct LanguageStats {
+    pub tasks: usize,
+    pub pomodoros: u32,
+    pub focus_time: Duration,
+}
+
+/// A finished work session, kept so stats can be grouped by day.
+pub struct Session {
+    pub finished_at: SystemTime,
+    pub duration: Duration,
+}
+
+/// Sessions finished on each of the last `days` days, oldest first.
+pub fn sessions_per_day<'a>(
+    sessions: impl IntoIterator<Item = &'a Session>,
+    days: usize,
+) -> Vec<u32> {
+    let today = local_day(SystemTime::now());
+    let mut counts = vec![0; days];
+    for session in sessions {
+        let age = today - local_day(session.finished_at);
+        if (0..days as i64).contains(&age) {
+            counts[days - 1 - age as usize] += 1;
+        }
+    }
+    counts
+}
+
+/// Sessions finished in each hour of the local day, across all days.
+pub fn sessions_per_hour<'a>(sessions: impl IntoIterator<Item = &'a Session>) -> [u32; 24] {
+    let mut counts = [0; 24];
+    for session in sessions {
+        counts[local_hour(session.finished_at)] += 1;
+    }
+    counts
+}
+
+impl Task {
+    pub fn new(name: String, language: String) -> Self {
+        Self {
+            name,
+            language,
+            pomodoro_state: PomodoroState::Idle,
+            pomodoro_start: None,
+            completed_pomodoros: 0,
+            session_duration: None,
+            history: Vec::new(),
+            total_focus_time: Duration::ZERO,
+            last_worked: None,
+            checklist: Vec::new(),
+            paused: None,
+            priority: Priority::Normal,
+            estimated_pomodoros: None,
+            created_at: SystemTime::now(),
+            pinned: false,
+            done: false,
+            recurring: false,
+            reset_at: None,
+            lifetime_pomodoros: 0,
+            label: None,
+            interruptions: 0,
+            total_break_time: Duration::ZERO,
+            extensions: 0,
+            intent: None,
+        }
+    }
+
+    pub fn work_duration(&self) -> Duration {
+        self.session_duration.unwrap_or(WORK_DURATION)
+    }
+
+    /// A break follows the pomodoro just counted, so it is long when that
+    /// count lands on the long-break interval, `every`.
+    pub fn is_long_break(&self, every: u32) -> bool {
+        self.completed_pomodoros > 0 && self.completed_pomodoros.is_multiple_of(every)
+    }
+
+    pub fn break_duration(&self, every: u32) -> Duration {
+        if self.is_long_break(every) {
+            LONG_BREAK_DURATION
+        } else {
+            BREAK_DURATION
+        }
+    }
+
+    /// Whether finishing the current work session leads to a long break.
+    pub fn next_break_is_long(&self, every: u32) -> bool {
+        (self.completed_pomodoros + 1).is_multiple_of(every)
+    }
+
+    /// Roughly how long the pomodoros left on the estimate will take,
+    /// counting the breaks between them but not the one after the last.
+    /// `None` without an estimate or once it has been reached.
+    pub fn time_to_finish(&self, long_break_every: u32) -> Option<Duration> {
+        let remaining = self
+            .estimated_pomodoros?
+            .checked_sub(self.completed_pomodoros)
+            .filter(|&left| left > 0)?;
+        let breaks: Duration = (1..remaining)
+            .map(|k| {
+                if (self.completed_pomodoros + k).is_multiple_of(long_break_every) {
+                    LONG_BREAK_DURATION
+                } else {
+                    BREAK_DURATION
+                }
+            })
+            .sum();
+        Some(WORK_DURATION * remaining + breaks)
+    }
+
+    /// Time spent in the current phase, whether running or paused.
+    pub fn elapsed(&self, now: Instant) -> Option<Duration> {
+        self.pomodoro_start
+            .map(|start| now.saturating_duration_since(start))
+            .or(self.paused)
+    }
+
+    /// Drops whatever phase is running or paused and returns to Idle.
+    pub fn stop_timer(&mut self) {
+        self.pomodoro_state = PomodoroState::Idle;
+        self.pomodoro_start = None;
+        self.paused = None;
+        self.session_duration = None;
+        self.extensions = 0;
+        self.intent = None;
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        if let Some(start) = self.pomodoro_start.take() {
+            self.paused = Some(now.saturating_duration_since(start));
+        }
+    }
+
+    pub fn resume(&mut self, now: Instant) {
+        if let Some(elapsed) = self.paused.take() {
+            self.pomodoro_start = now.checked_sub(elapsed).or(Some(now));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_to_finish_counts_the_breaks_in_between() {
+        let mut task = Task::new("Refactor".to_string(), "Rust".to_string());
+        assert_eq!(task.time_to_finish(LONG_BREAK_EVERY), None);
+
+        task.estimated_pomodoros = Some(5);
+        task.completed_pomodoros = 2;
+        // Pomodoros 3, 4 and 5, with a short break after 3 and a long one after 4.
+        assert_eq!(
+            task.time_to_finish(LONG_BREAK_EVERY),
+            Some(WORK_DURATION * 3 + BREAK_DURATION + LONG_BREAK_DURATION)
+        );
+
+        task.completed_pomodoros = 5;
+        assert_eq!(task.time_to_finish(LONG_BREAK_EVERY), None);
+
+        task.completed_pomodoros = 2;
+        assert_eq!(
+            task.time_to_finish(1),
+            Some((WORK_DURATION + LONG_BREAK_DURATION) * 3 - LONG_BREAK_DURATION)
+        );
+    }
+
+    #[test]
+    fn sessions_per_hour_buckets_by_hour_of_day() {
+        let now = SystemTime::now();
+        let session = |finished_at| Session {
+            finished_at,
+            duration: WORK_DURATION,
+        };
+        let sessions = [
+            session(now),
+            session(now - Duration::from_secs(24 * 60 * 60)),
+            session(now - Duration::from_secs(60 * 60)),
+        ];
+
+        let counts = sessions_per_hour(&sessions);
+        assert_eq!(counts[local_hour(now)], 2);
+        assert_eq!(counts.iter().sum::<u32>(), 3);
+    }
+}